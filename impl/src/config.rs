@@ -0,0 +1,241 @@
+//! Project-wide default options, read from `vk-shader-macros.toml` in the calling crate's root
+//! and from environment variables
+
+use crate::build::{self, BuildOptions};
+use crate::parse;
+use proc_macro2::Span;
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::{env, fs};
+use toml::{Table, Value};
+
+const FILE_NAME: &str = "vk-shader-macros.toml";
+
+const OPTIMIZE_VAR: &str = "VK_SHADER_MACROS_OPTIMIZE";
+const DEBUG_VAR: &str = "VK_SHADER_MACROS_DEBUG";
+const TARGET_VAR: &str = "VK_SHADER_MACROS_TARGET";
+
+/// Environment variables read by the macros, which must be tracked
+/// so that changing them expands the macros again
+const ENV_VARS: &[&str] = &[OPTIMIZE_VAR, DEBUG_VAR, TARGET_VAR, parse::INCLUDE_PATH_VAR];
+
+/// Environment variables read by the macros, including the ones of the shader libraries
+pub fn tracked_env_vars() -> Vec<String> {
+    ENV_VARS
+        .iter()
+        .map(|&var| var.to_owned())
+        .chain(library_vars().map(|(var, ..)| var))
+        .collect()
+}
+
+/// Shader libraries of dependencies, as the name of the library and its absolute directory
+///
+/// A library crate declares `links = "<name>"` and prints `cargo:shaders=<dir>` from its build
+/// script. Cargo passes this to the build scripts of dependent crates as `DEP_<NAME>_SHADERS`,
+/// which must forward it to the compiler with `cargo:rustc-env`, so that it is visible here.
+///
+/// The directories are only checked when a shader includes a file of the library, so that a
+/// broken library doesn't affect the other shaders.
+pub fn shader_libraries() -> Vec<(String, String)> {
+    library_vars()
+        .map(|(_, name, dir)| {
+            let dir =
+                std::path::absolute(&dir).map_or(dir, |dir| dir.to_string_lossy().into_owned());
+            (name, dir)
+        })
+        .collect()
+}
+
+/// Variables of shader libraries, with the name of the library and the value
+fn library_vars() -> impl Iterator<Item = (String, String, String)> {
+    env::vars().filter_map(|(var, value)| {
+        let name = var
+            .strip_prefix("DEP_")?
+            .strip_suffix("_SHADERS")?
+            .to_owned();
+        Some((var, name, value))
+    })
+}
+
+/// Path of the configuration file of the calling crate, if it exists
+pub fn path() -> Option<PathBuf> {
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(FILE_NAME);
+    path.is_file().then_some(path)
+}
+
+/// Defaults of the options, from the enabled features and the configuration file, in increasing
+/// priority
+///
+/// Include paths of the configuration file are not included,
+/// as they are searched after the ones of the invocation, see [`include_paths`].
+/// Environment variables are applied after the options of the invocation, see [`apply_env`].
+pub fn defaults() -> syn::Result<BuildOptions> {
+    let mut out = BuildOptions::default();
+    if let Some(config) = load()? {
+        apply_config(&mut out, &config)?;
+    }
+    Ok(out)
+}
+
+fn apply_config(out: &mut BuildOptions, config: &Table) -> syn::Result<()> {
+    for (key, value) in config {
+        match key.as_str() {
+            "target" => {
+                let value = str(key, value)?;
+                let (env, version) = parse::target(value)
+                    .ok_or_else(|| error(format!("unknown target `{value}`")))?;
+                out.target_env = env;
+                out.target_version = version;
+            }
+            "spirv" => {
+                let value = str(key, value)?;
+                out.spirv_version = Some(
+                    parse::spirv_version(value)
+                        .ok_or_else(|| error(format!("unknown SPIR-V version `{value}`")))?,
+                );
+            }
+            "optimize" => {
+                let value = str(key, value)?;
+                out.optimization = parse::optimization_level(value)
+                    .ok_or_else(|| error(format!("unknown optimization level `{value}`")))?;
+            }
+            "debug" => {
+                out.debug = value
+                    .as_bool()
+                    .ok_or_else(|| error(format!("`{key}` must be a bool")))?;
+            }
+            "default_kind" => {
+                let value = str(key, value)?;
+                out.default_kind = Some(
+                    build::extension_kind(value)
+                        .and_then(build::default_kind)
+                        .ok_or_else(|| error(format!("unknown shader kind `{value}`")))?,
+                );
+            }
+            "kind_dirs" => {
+                let kind_dirs = value
+                    .as_table()
+                    .ok_or_else(|| error(format!("`{key}` must be a table")))?;
+                for (name, value) in kind_dirs {
+                    let value = str(name, value)?;
+                    let kind = build::extension_kind(value)
+                        .ok_or_else(|| error(format!("unknown shader kind `{value}`")))?;
+                    out.kind_dirs
+                        .to_mut()
+                        .push((Cow::Owned(name.clone()), kind));
+                }
+            }
+            "warnings" => {
+                let value = str(key, value)?;
+                out.warnings = parse::warnings_level(value)
+                    .ok_or_else(|| error(format!("unknown warnings level `{value}`")))?;
+            }
+            "defines" => {
+                let defines = value
+                    .as_table()
+                    .ok_or_else(|| error(format!("`{key}` must be a table")))?;
+                for (name, value) in defines {
+                    let value =
+                        define_value(value).map_err(|e| error(format!("define `{name}`: {e}")))?;
+                    out.definitions
+                        .to_mut()
+                        .push((Cow::Owned(name.clone()), Some(Cow::Owned(value))));
+                }
+            }
+            "prelude" => {
+                out.prelude = Some(Cow::Owned(
+                    parse::prelude_path(str(key, value)?).map_err(error)?,
+                ));
+            }
+            // read by `include_paths`
+            "include_paths" => {}
+            _ => return Err(error(format!("unknown option `{key}`"))),
+        }
+    }
+
+    Ok(())
+}
+
+/// Override options with the environment variables, which take precedence over the options of the
+/// invocation so that every shader can be rebuilt e.g. without optimizations
+pub fn apply_env(out: &mut BuildOptions) -> syn::Result<()> {
+    let env_error = |var: &str, value: &str| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("{var}: unsupported value `{value}`"),
+        )
+    };
+
+    if let Ok(value) = env::var(OPTIMIZE_VAR) {
+        out.optimization =
+            parse::optimization_level(&value).ok_or_else(|| env_error(OPTIMIZE_VAR, &value))?;
+    }
+    if let Ok(value) = env::var(DEBUG_VAR) {
+        out.debug = match value.as_str() {
+            "1" | "true" => true,
+            "0" | "false" => false,
+            _ => return Err(env_error(DEBUG_VAR, &value)),
+        };
+    }
+    if let Ok(value) = env::var(TARGET_VAR) {
+        let (env, version) = parse::target(&value).ok_or_else(|| env_error(TARGET_VAR, &value))?;
+        out.target_env = env;
+        out.target_version = version;
+    }
+
+    Ok(())
+}
+
+/// Absolute include paths of the configuration file
+pub fn include_paths() -> syn::Result<Vec<String>> {
+    let config = match load()? {
+        Some(config) => config,
+        None => return Ok(Vec::new()),
+    };
+    let paths = match config.get("include_paths") {
+        Some(paths) => paths,
+        None => return Ok(Vec::new()),
+    };
+
+    paths
+        .as_array()
+        .ok_or_else(|| error("`include_paths` must be an array".to_owned()))?
+        .iter()
+        .map(|path| {
+            let path = str("include_paths", path)?;
+            parse::include_path(path).map_err(error)
+        })
+        .collect()
+}
+
+fn load() -> syn::Result<Option<Table>> {
+    let path = match path() {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let src = fs::read_to_string(&path).map_err(|e| error(e.to_string()))?;
+    src.parse::<Table>()
+        .map(Some)
+        .map_err(|e| error(e.to_string()))
+}
+
+/// Format a define value as GLSL, like the literals of the `define` option
+fn define_value(value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::String(value) => parse::define_string(value.clone())?,
+        Value::Integer(value) => value.to_string(),
+        Value::Float(value) if value.is_finite() => parse::define_float(&value.to_string()),
+        Value::Boolean(value) => value.to_string(),
+        _ => return Err("unsupported value".to_owned()),
+    })
+}
+
+fn str<'a>(key: &str, value: &'a Value) -> syn::Result<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| error(format!("`{key}` must be a string")))
+}
+
+fn error(message: String) -> syn::Error {
+    syn::Error::new(Span::call_site(), format!("{FILE_NAME}: {message}"))
+}
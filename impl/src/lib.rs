@@ -2,79 +2,492 @@ extern crate proc_macro;
 
 #[path = "../../shared/build.rs"]
 mod build;
+mod config;
 mod parse;
 
-use std::fs;
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
 
 use proc_macro::TokenStream;
 use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, LitStr, Result, Token};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parse_macro_input, Ident, LitStr, Result, Token, Visibility};
 
-use self::build::{BuildOptions, Builder, Output};
+use self::build::{BuildOptions, Builder, Output, SourceLanguage};
+use self::parse::{CfgVariants, Options};
+
+/// Path of the file containing the proc macro invocation
+///
+/// When the file is unavailable, e.g. in code generated by another macro, this is a file named
+/// after the span in the crate root, which may not exist, so that relative includes are resolved
+/// against the crate root.
+fn call_site_file() -> PathBuf {
+    let span = proc_macro::Span::call_site();
+    match span.local_file().and_then(|path| path.canonicalize().ok()) {
+        Some(path) => path,
+        None => {
+            let file = PathBuf::from(span.file());
+            let name = file.file_name().unwrap_or(OsStr::new("<unknown>"));
+            manifest_dir().join(name)
+        }
+    }
+}
+
+/// Root directory of the crate invoking the proc macro
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+}
+
+/// Root directory of the workspace containing the crate invoking the proc macro, which is the
+/// nearest ancestor with a `Cargo.toml` declaring a workspace, or the crate root if there is none
+fn workspace_dir() -> PathBuf {
+    let manifest_dir = manifest_dir();
+    let is_workspace = |dir: &Path| {
+        fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+            .is_some_and(|manifest| manifest.contains_key("workspace"))
+    };
+    manifest_dir
+        .ancestors()
+        .find(|dir| is_workspace(dir))
+        .unwrap_or(&manifest_dir)
+        .to_path_buf()
+}
+
+/// Resolve a path relative to the file of the proc macro invocation, or to the crate root if the
+/// file is unavailable
+///
+/// Paths starting with `$manifest/` or `$workspace/` are resolved relative to the crate root or
+/// the workspace root instead.
+fn resolve_path(path_lit: &LitStr) -> Result<PathBuf> {
+    let value = path_lit.value();
+    let path = if let Some(path) = value.strip_prefix("$manifest/") {
+        manifest_dir().join(path)
+    } else if let Some(path) = value.strip_prefix("$workspace/") {
+        workspace_dir().join(path)
+    } else {
+        let local_dir = proc_macro::Span::call_site()
+            .local_file()
+            .and_then(|file| file.parent().map(Path::to_path_buf))
+            .unwrap_or_else(manifest_dir);
+        local_dir.join(value)
+    };
+
+    path.canonicalize()
+        .map_err(|e| syn::Error::new(path_lit.span(), format!("{}: {e}", path.display())))
+}
 
 struct IncludeGlsl {
     output: Output,
     builder: Builder,
 }
 
-impl Parse for IncludeGlsl {
+impl Parse for CfgVariants<IncludeGlsl> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        IncludeGlsl::parse_with_language(input, SourceLanguage::Glsl)
+    }
+}
+
+impl IncludeGlsl {
+    fn parse_with_language(
+        input: ParseStream,
+        source_language: SourceLanguage,
+    ) -> Result<CfgVariants<Self>> {
+        let path_lit = input.parse::<LitStr>()?;
+        let path = resolve_path(&path_lit)?;
+
+        let src = fs::read_to_string(&path).map_err(|e| syn::Error::new(path_lit.span(), e))?;
+
+        let mut options = if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            input.parse::<Options>()?
+        } else {
+            Options::for_invocation()?
+        };
+        options.build.source_language = source_language;
+        // WGSL has no preprocessor, so the defines of the configuration file don't apply
+        #[cfg(feature = "wgsl")]
+        if source_language == SourceLanguage::Wgsl {
+            options.remove_config_defines();
+        }
+        if source_language == SourceLanguage::Hlsl && options.build.entry_point.is_none() {
+            return Err(syn::Error::new(
+                path_lit.span(),
+                "HLSL shaders require an `entry` option",
+            ));
+        }
+
+        options.compile(|options| {
+            let builder = Builder {
+                src: src.clone(),
+                path: path.clone(),
+                options,
+            };
+            builder
+                .clone()
+                .build()
+                .map_err(|e| syn::Error::new(path_lit.span(), e))
+                .map(|output| Self { output, builder })
+        })
+    }
+}
+
+struct IncludeHlsl(CfgVariants<IncludeGlsl>);
+
+impl Parse for IncludeHlsl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        IncludeGlsl::parse_with_language(input, SourceLanguage::Hlsl).map(Self)
+    }
+}
+
+#[cfg(feature = "wgsl")]
+struct IncludeWgsl(CfgVariants<IncludeGlsl>);
+
+#[cfg(feature = "wgsl")]
+impl Parse for IncludeWgsl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        IncludeGlsl::parse_with_language(input, SourceLanguage::Wgsl).map(Self)
+    }
+}
+
+struct IncludeGlslStages(Vec<IncludeGlsl>);
+
+impl Parse for CfgVariants<IncludeGlslStages> {
     fn parse(input: ParseStream) -> Result<Self> {
         let path_lit = input.parse::<LitStr>()?;
+        let path = resolve_path(&path_lit)?;
+
+        let src = fs::read_to_string(&path).map_err(|e| syn::Error::new(path_lit.span(), e))?;
 
-        // resolve path relative to the file of the proc macro invocation
-        let local_file = proc_macro::Span::call_site().local_file().unwrap();
-        let local_dir = local_file.parent().unwrap();
+        input.parse::<Token![,]>()?;
+        let key = input.parse::<Ident>()?;
+        if key != "stages" {
+            return Err(syn::Error::new(key.span(), "expected `stages`"));
+        }
+        input.parse::<Token![:]>()?;
+        let content;
+        bracketed!(content in input);
+        let stages = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+
+        let options = if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            input.parse::<Options>()?
+        } else {
+            Options::for_invocation()?
+        };
+        if options.build.kind.is_some() {
+            return Err(syn::Error::new(
+                path_lit.span(),
+                "the shader kind of each stage is determined by `stages`",
+            ));
+        }
+
+        options.compile(|options| {
+            stages
+                .iter()
+                .map(|stage| {
+                    let (kind, define) = build::extension_kind(&stage.to_string())
+                        .and_then(|kind| Some((kind, parse::stage_define(kind)?)))
+                        .ok_or_else(|| syn::Error::new(stage.span(), "unknown shader stage"))?;
+
+                    let mut options = options.clone();
+                    options.kind = Some(kind);
+                    options
+                        .definitions
+                        .to_mut()
+                        .push((Cow::Borrowed(define), None));
+
+                    let builder = Builder {
+                        src: src.clone(),
+                        path: path.clone(),
+                        options,
+                    };
+                    builder
+                        .clone()
+                        .build()
+                        .map_err(|e| syn::Error::new(stage.span(), e))
+                        .map(|output| IncludeGlsl { output, builder })
+                })
+                .collect::<Result<_>>()
+                .map(IncludeGlslStages)
+        })
+    }
+}
+
+struct IncludeGlslDir {
+    visibility: Visibility,
+    name: Ident,
+    /// File name, item name and compiled shader of each file in the directory
+    shaders: Vec<(String, Ident, IncludeGlsl)>,
+}
 
-        let path = local_dir.join(path_lit.value());
-        let path = path
-            .canonicalize()
+impl Parse for IncludeGlslDir {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let visibility = input.parse::<Visibility>()?;
+        input.parse::<Token![mod]>()?;
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let path_lit = input.parse::<LitStr>()?;
+        let dir = resolve_path(&path_lit)?;
+
+        let options = if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            input.parse::<Options>()?.without_define_if()?
+        } else {
+            BuildOptions::for_invocation()?
+        };
+        if options.kind.is_some() {
+            return Err(syn::Error::new(
+                path_lit.span(),
+                "the shader kind of each file is inferred from its extension",
+            ));
+        }
+
+        let mut paths = fs::read_dir(&dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<std::io::Result<Vec<_>>>()
+            })
             .map_err(|e| syn::Error::new(path_lit.span(), e))?;
+        // directory names are not used to infer kinds here, as they would apply to every file
+        paths.retain(|path| path.is_file() && build::infer_kind(path, &[]).is_some());
+        paths.sort();
+
+        let mut shaders = Vec::<(String, Ident, IncludeGlsl)>::new();
+        for path in paths {
+            let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let item_name = parse::item_name(&file_name);
+            if let Some((other, ..)) = shaders.iter().find(|(_, x, _)| *x == item_name) {
+                return Err(syn::Error::new(
+                    path_lit.span(),
+                    format!("`{file_name}` and `{other}` would both be named `{item_name}`"),
+                ));
+            }
+
+            let src = fs::read_to_string(&path).map_err(|e| syn::Error::new(path_lit.span(), e))?;
+            let builder = Builder {
+                src,
+                path,
+                options: options.clone(),
+            };
+            let output = builder
+                .clone()
+                .build()
+                .map_err(|e| syn::Error::new(path_lit.span(), e))?;
+            shaders.push((file_name, item_name, IncludeGlsl { output, builder }));
+        }
+
+        Ok(Self {
+            visibility,
+            name,
+            shaders,
+        })
+    }
+}
+
+struct IncludeGlslPermutations {
+    visibility: Visibility,
+    name: Ident,
+    defines: Vec<Ident>,
+    /// Compiled shader for each combination of `defines`, indexed by the bits of the combination
+    permutations: Vec<IncludeGlsl>,
+}
+
+impl Parse for IncludeGlslPermutations {
+    fn parse(input: ParseStream) -> Result<Self> {
+        const MAX_DEFINES: usize = 16;
+
+        let visibility = input.parse::<Visibility>()?;
+        input.parse::<Token![struct]>()?;
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let path_lit = input.parse::<LitStr>()?;
+        let path = resolve_path(&path_lit)?;
 
         let src = fs::read_to_string(&path).map_err(|e| syn::Error::new(path_lit.span(), e))?;
 
+        input.parse::<Token![,]>()?;
+        let key = input.parse::<Ident>()?;
+        if key != "permute" {
+            return Err(syn::Error::new(key.span(), "expected `permute`"));
+        }
+        input.parse::<Token![:]>()?;
+        let content;
+        let brackets = bracketed!(content in input);
+        let defines = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if defines.len() > MAX_DEFINES {
+            return Err(syn::Error::new(
+                brackets.span.join(),
+                format!("at most {MAX_DEFINES} defines can be permuted"),
+            ));
+        }
+        for (i, define) in defines.iter().enumerate() {
+            if defines[..i].contains(define) {
+                return Err(syn::Error::new(define.span(), "duplicate define"));
+            }
+        }
+
         let options = if input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
-            input.parse::<BuildOptions>()?
+            input.parse::<Options>()?.without_define_if()?
         } else {
-            BuildOptions::default()
+            BuildOptions::for_invocation()?
         };
 
-        let builder = Builder { src, path, options };
-        builder
-            .clone()
-            .build()
-            .map_err(|e| syn::Error::new(path_lit.span(), e))
-            .map(|output| Self { output, builder })
+        let permutations = (0..1_u32 << defines.len())
+            .map(|bits| {
+                let mut options = options.clone();
+                for (i, define) in defines.iter().enumerate() {
+                    if bits & (1 << i) != 0 {
+                        options
+                            .definitions
+                            .to_mut()
+                            .push((Cow::Owned(define.to_string()), None));
+                    }
+                }
+
+                let builder = Builder {
+                    src: src.clone(),
+                    path: path.clone(),
+                    options,
+                };
+                builder
+                    .clone()
+                    .build()
+                    .map_err(|e| syn::Error::new(path_lit.span(), e))
+                    .map(|output| IncludeGlsl { output, builder })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            visibility,
+            name,
+            defines,
+            permutations,
+        })
     }
 }
 
-struct Glsl(Output);
+struct IncludeSpirv(Output);
+
+impl Parse for IncludeSpirv {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path_lit = input.parse::<LitStr>()?;
+        let path = resolve_path(&path_lit)?;
+
+        let bytes = fs::read(&path).map_err(|e| syn::Error::new(path_lit.span(), e))?;
+        let spv = build::spirv_words(&bytes).map_err(|e| syn::Error::new(path_lit.span(), e))?;
+
+        let entry_point = build::spirv_entry_point(&spv)
+            .ok_or_else(|| syn::Error::new(path_lit.span(), "SPIR-V binary has no entry point"))?;
+        #[cfg(feature = "reflection")]
+        let entry_points = build::reflect(&spv).map_err(|e| syn::Error::new(path_lit.span(), e))?;
+
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(Self(Output {
+            sources: vec![path.to_string_lossy().into_owned()],
+            spv,
+            entry_point,
+            warnings: Vec::new(),
+            #[cfg(feature = "reflection")]
+            entry_points,
+        }))
+    }
+}
+
+struct Glsl(CfgVariants<Output>);
 
 impl Parse for Glsl {
     fn parse(input: ParseStream) -> Result<Self> {
-        let options = if !input.peek(LitStr) {
-            let build_options = input.parse::<BuildOptions>()?;
+        Self::parse_with_language(input, SourceLanguage::Glsl)
+    }
+}
+
+impl Glsl {
+    fn parse_with_language(input: ParseStream, source_language: SourceLanguage) -> Result<Self> {
+        let mut options = if !input.peek(LitStr) {
+            let options = input.parse::<Options>()?;
             input.parse::<Token![,]>()?;
-            build_options
+            options
         } else {
-            BuildOptions::default()
+            Options::for_invocation()?
         };
 
         let src_lit = input.parse::<LitStr>()?;
         let src = src_lit.value();
 
-        let path = proc_macro::Span::call_site()
-            .local_file()
-            .unwrap()
-            .canonicalize()
-            .unwrap();
+        options.build.source_language = source_language;
+        if source_language == SourceLanguage::Hlsl && options.build.entry_point.is_none() {
+            return Err(syn::Error::new(
+                src_lit.span(),
+                "HLSL shaders require an `entry` option",
+            ));
+        }
+
+        let path = call_site_file();
 
         if input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
         }
 
-        let builder = Builder { src, path, options };
+        options
+            .compile(|options| {
+                let builder = Builder {
+                    src: src.clone(),
+                    path: path.clone(),
+                    options,
+                };
+                builder
+                    .build()
+                    .map_err(|e| syn::Error::new(src_lit.span(), e))
+            })
+            .map(Self)
+    }
+}
+
+struct Hlsl(Glsl);
+
+impl Parse for Hlsl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Glsl::parse_with_language(input, SourceLanguage::Hlsl).map(Self)
+    }
+}
+
+struct Spvasm(Output);
+
+impl Parse for Spvasm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = if !input.peek(LitStr) {
+            let build_options = input.parse::<Options>()?.without_define_if()?;
+            input.parse::<Token![,]>()?;
+            build_options
+        } else {
+            BuildOptions::for_invocation()?
+        };
+        options.kind = Some(build::ShaderKind::SpirvAssembly);
+
+        let src_lit = input.parse::<LitStr>()?;
+        let src = src_lit.value();
+
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+
+        let builder = Builder {
+            src,
+            path: call_site_file(),
+            options,
+        };
         builder
             .build()
             .map_err(|e| syn::Error::new(src_lit.span(), e))
@@ -89,7 +502,10 @@ impl Parse for Glsl {
 /// static VERT: ShaderData = include_glsl!("example.vert");
 /// ```
 ///
-/// The path is resolved relative to the current file.
+/// The path is resolved relative to the current file. Paths starting with `$manifest/` or
+/// `$workspace/` are resolved relative to the root of the crate or of its workspace instead,
+/// e.g. `include_glsl!("$manifest/shaders/example.vert")`. The other macros taking a path
+/// support the same prefixes.
 ///
 /// # Options
 ///
@@ -97,20 +513,239 @@ impl Parse for Glsl {
 /// - `kind: <kind>` - Specify shader kind. Valid kinds are the same as the recognized file
 ///    extensions: `vert`, `frag`, `comp`, `geom`, `tesc`, `tese`, `spvasm`, `rgen`, `rahit`,
 ///    `rchit`, `rmiss`, `rint`, `rcall`, `task`, and `mesh`. If omitted, kind is inferred from the
-///    file's extension, or a pragma in the source.
-/// - `version: <version>` - Specify GLSL version. If omitted, version must be specified in the
+///    file's extension, which may be followed by another, e.g. `lighting.frag.glsl`, from the
+///    `kind_dir` directories, or from a pragma in the source.
+/// - `default_kind: <kind>` - Specify the shader kind used when it can neither be inferred from the
+///   file name nor from a `#pragma shader_stage` in the source.
+/// - `kind_dir: "<name>" => <kind>` - Infer the given kind for shaders in a directory called `name`,
+///   e.g. `kind_dir: "compute" => comp`. May be repeated.
+/// - `version: <version> [profile]` - Specify GLSL version, and optionally the profile: `core`,
+///    `compatibility` or `es`, e.g. `version: 310 es`. If omitted, version must be specified in the
 ///    source with `#version`
+/// - `profile: <profile>` - Specify the GLSL profile forced along with `version`
 /// - `strip` - Omit debug info (set as default by enabling the `strip` feature)
 /// - `debug` - Force debug info, even with the `strip` feature enabled
-/// - `define: <name> ["value"]` - Define the preprocessor macro `<name>` as `value`
+/// - `define: <name> [value]` - Define the preprocessor macro `<name>` as `value`. The value may be
+///   a string, which is used verbatim, or a bool, integer or float literal, e.g. `define: COUNT 16`,
+///   `define: SCALE -0.5` or `define: ENABLED true`. `u32` and `f64` suffixes map to the GLSL `u`
+///   and `lf` suffixes.
 /// - `optimize: <level>` - Specify optimization level. Supported values are: `zero`, `size`, and
 ///   `performance`.  If omitted, will default to `performance`.
 /// - `target: <target>` - Specify target environment. Supported values: `vulkan1_0`, `vulkan1_1`,
-///   `vulkan1_2`. Defaults to `vulkan1_0`.
+///   `vulkan1_2`, `vulkan1_3`, `vulkan1_4`, and `opengl4_5` (for `GL_ARB_gl_spirv`). Defaults to
+///   `vulkan1_0`.
+/// - `spirv: <version>` - Specify the SPIR-V version, from `1_0` to `1_6`. Defaults to the version
+///   required by the target environment.
+/// - `entry: "<name>"` - Specify the name of the entry point. For GLSL, the function is always
+///   called `main` in the source, and is renamed in the generated SPIR-V. Defaults to `main`.
+/// - `define_if: (<predicate>) <name> [value]` - Like `define`, but only when the `cfg` predicate
+///   holds in the calling crate, e.g. `define_if: (debug_assertions) DEBUG_VIS` or
+///   `define_if: (feature = "shadows") SHADOWS`. A variant is compiled for each combination of
+///   predicates, and selected with `cfg!`. Not supported by `include_glsl_dir!`,
+///   `include_glsl_permutations!` and `spvasm!`.
+/// - `warnings: <level>` - Handling of compiler warnings: `deny` fails the build, `warn` reports
+///   each warning as a Rust warning on the macro invocation, and `allow` ignores them. Defaults to
+///   `deny`, unless the `default-warnings-warn` or `default-warnings-allow` feature is enabled.
+/// - `auto_bind: <bool>` - Assign bindings to resources declared without `layout(binding = ...)`.
+///   Defaults to `true`.
+/// - `auto_map_locations: <bool>` - Assign locations to inputs and outputs declared without
+///   `layout(location = ...)`. Defaults to `false`.
+/// - `binding_base: <kind> <base>` - First binding assigned by `auto_bind` to resources of the given
+///   kind: `texture`, `sampler`, `image`, `uniform_buffer` or `storage_buffer`.
+/// - `set_binding_shift: <set> <shift>` - Add `shift` to the binding of every resource in
+///   descriptor set `set`, including explicit bindings.
+/// - `prelude: "<path>"` - Prepend the file at `path`, relative to the crate root, to the source,
+///   e.g. for common `#version` and `#extension` directives. Line numbers in diagnostics refer to
+///   the source. Only applies to GLSL. `prelude: none` disables the prelude of
+///   `vk-shader-macros.toml`.
+/// - `include_path: "<dir>"` - Add a directory searched for `#include <...>`, relative to the
+///   crate root. May be repeated; directories are searched in the order given.
+/// - `virtual_include: "<name>" => "<source>"` - Serve `source` for `#include "<name>"` and
+///   `#include <name>`, without reading the filesystem. May be repeated.
+///
+/// The entry point name is available as `ShaderData::entry_point`.
+///
+/// Defaults for the options may be set in a `vk-shader-macros.toml` file in the crate root, and
+/// the `optimize`, `strip` and `target` options may be overridden for every shader with
+/// environment variables, see the crate's README.
+///
+/// Standard includes are searched in the `include_path` directories, then in the `include_paths`
+/// of `vk-shader-macros.toml`, then in the directories listed in the
+/// `VK_SHADER_MACROS_INCLUDE_PATH` environment variable (separated like `PATH`), and finally in
+/// the crate root. The variable can be set for a whole project in the `[env]` section of
+/// `.cargo/config.toml`.
+///
+/// `#include <name/path>` resolves to `path` in the shader library `name` of a dependency, when
+/// the `DEP_<NAME>_SHADERS` environment variable is set by the build script, see the crate's
+/// README.
 #[proc_macro]
 pub fn include_glsl(tokens: TokenStream) -> TokenStream {
-    let include_glsl = parse_macro_input!(tokens as IncludeGlsl);
-    include_glsl.to_token_stream().into()
+    let include_glsl = parse_macro_input!(tokens as CfgVariants<IncludeGlsl>);
+    include_glsl.expand(ToTokens::to_token_stream).into()
+}
+
+/// Compile several shader stages from a single GLSL source file
+///
+/// ```
+/// use vk_shader_macros::{include_glsl_stages, ShaderData};
+/// static MATERIAL: (ShaderData, ShaderData) =
+///     include_glsl_stages!("material.glsl", stages: [vert, frag]);
+/// ```
+///
+/// The file is compiled once for each of the listed stages, with the stage's kind and a
+/// preprocessor macro naming the stage defined, so that the source can select the code of each
+/// stage with e.g. `#ifdef VERTEX`. The result is a tuple of `ShaderData`, in the order of
+/// `stages`.
+///
+/// | Stage   | Macro             |
+/// |---------|-------------------|
+/// | `vert`  | `VERTEX`          |
+/// | `frag`  | `FRAGMENT`        |
+/// | `comp`  | `COMPUTE`         |
+/// | `geom`  | `GEOMETRY`        |
+/// | `tesc`  | `TESS_CONTROL`    |
+/// | `tese`  | `TESS_EVALUATION` |
+/// | `rgen`  | `RAY_GENERATION`  |
+/// | `rahit` | `ANY_HIT`         |
+/// | `rchit` | `CLOSEST_HIT`     |
+/// | `rmiss` | `MISS`            |
+/// | `rint`  | `INTERSECTION`    |
+/// | `rcall` | `CALLABLE`        |
+/// | `task`  | `TASK`            |
+/// | `mesh`  | `MESH`            |
+///
+/// # Options
+///
+/// Accepts the same options as [`include_glsl!`] after `stages`, except for `kind`.
+#[proc_macro]
+pub fn include_glsl_stages(tokens: TokenStream) -> TokenStream {
+    let include_glsl_stages = parse_macro_input!(tokens as CfgVariants<IncludeGlslStages>);
+    include_glsl_stages.expand(ToTokens::to_token_stream).into()
+}
+
+/// Compile all GLSL source files in a directory into a module of SPIR-V constants
+///
+/// ```
+/// use vk_shader_macros::include_glsl_dir;
+/// include_glsl_dir!(pub mod shaders = "shaders", optimize: size);
+///
+/// let blur = &shaders::BLUR_COMP;
+/// for (file_name, shader) in shaders::ALL {
+///     // ...
+/// }
+/// ```
+///
+/// The path is resolved relative to the current file. Every file in the directory with an
+/// extension recognized by the `kind` option of [`include_glsl!`] is compiled with its kind inferred
+/// from the extension, and becomes a `pub static` item named after the file in upper case, with
+/// characters that can't be used in identifiers replaced by `_`. The module also contains
+/// `ALL: &[(&str, &ShaderData)]`, listing the file name and shader of every file.
+/// Subdirectories are not included.
+///
+/// Changes to the included files cause a rebuild, but files added to or removed from the directory
+/// are not tracked. To pick them up automatically, add a build script to your crate that prints
+/// `cargo:rerun-if-changed=<directory>`.
+///
+/// # Options
+///
+/// Accepts the same options as [`include_glsl!`] after the path, except for `kind`. The options
+/// apply to every file.
+#[proc_macro]
+pub fn include_glsl_dir(tokens: TokenStream) -> TokenStream {
+    let include_glsl_dir = parse_macro_input!(tokens as IncludeGlslDir);
+    include_glsl_dir.to_token_stream().into()
+}
+
+/// Compile every combination of a set of preprocessor macros for a GLSL source file
+///
+/// ```
+/// use vk_shader_macros::include_glsl_permutations;
+/// include_glsl_permutations!(
+///     pub struct Uber = "uber.frag",
+///     permute: [SHADOWS, SKINNED, ALPHA_TEST],
+///     optimize: size
+/// );
+///
+/// let shader = (Uber::SHADOWS | Uber::ALPHA_TEST).shader();
+/// ```
+///
+/// The path is resolved relative to the current file. The file is compiled once for each
+/// combination of the macros listed in `permute`, which are defined without a value. This
+/// generates a bitflags-like struct with an associated constant for each macro, and a `shader`
+/// method returning the `ShaderData` compiled with exactly the macros that are set. The
+/// struct's `Default` value has no macros set. At most 16 macros can be permuted.
+///
+/// # Options
+///
+/// Accepts the same options as [`include_glsl!`] after `permute`. The options apply to every
+/// permutation.
+#[proc_macro]
+pub fn include_glsl_permutations(tokens: TokenStream) -> TokenStream {
+    let include_glsl_permutations = parse_macro_input!(tokens as IncludeGlslPermutations);
+    include_glsl_permutations.to_token_stream().into()
+}
+
+/// Compile an HLSL source file into a binary SPIR-V constant
+///
+/// ```
+/// use vk_shader_macros::{include_hlsl, ShaderData};
+/// static FRAG: ShaderData = include_hlsl!("tests/test.hlsl", kind: frag, entry: "PSMain");
+/// ```
+///
+/// The path is resolved relative to the current file.
+///
+/// # Options
+///
+/// Accepts the same options as [`include_glsl!`]. In addition, the `entry: "<name>"` option
+/// naming the entry point function is required, as HLSL entry points are rarely called `main`.
+/// Because HLSL has no equivalent of `#pragma shader_stage`, the shader kind must be given with
+/// `kind` unless it can be inferred from the file's extension.
+#[proc_macro]
+pub fn include_hlsl(tokens: TokenStream) -> TokenStream {
+    let IncludeHlsl(include_hlsl) = parse_macro_input!(tokens as IncludeHlsl);
+    include_hlsl.expand(ToTokens::to_token_stream).into()
+}
+
+/// Compile a WGSL source file into a binary SPIR-V constant
+///
+/// ```
+/// use vk_shader_macros::{include_wgsl, ShaderData};
+/// static FRAG: ShaderData = include_wgsl!("tests/test.wgsl", entry: "fs_main");
+/// ```
+///
+/// The path is resolved relative to the current file. WGSL is parsed and validated with
+/// [naga](https://github.com/gfx-rs/wgpu/tree/trunk/naga) rather than shaderc. Requires the `wgsl`
+/// feature.
+///
+/// # Options
+///
+/// Because a WGSL module may contain several entry points, the `entry` and `kind` options select
+/// the entry point to compile. They may be omitted if the module has only one matching entry point.
+/// The other options of [`include_glsl!`] are limited as when compiling GLSL with naga, see the
+/// crate's README, and `define` is rejected as WGSL has no preprocessor.
+#[cfg(feature = "wgsl")]
+#[proc_macro]
+pub fn include_wgsl(tokens: TokenStream) -> TokenStream {
+    let IncludeWgsl(include_wgsl) = parse_macro_input!(tokens as IncludeWgsl);
+    include_wgsl.expand(ToTokens::to_token_stream).into()
+}
+
+/// Include a precompiled SPIR-V binary
+///
+/// ```
+/// use vk_shader_macros::{include_spirv, ShaderData};
+/// static COMP: ShaderData = include_spirv!("tests/test.spv");
+/// ```
+///
+/// The path is resolved relative to the current file. The binary is checked for the SPIR-V magic
+/// number and word alignment at compile time, and byte-swapped if it was stored with the opposite
+/// endianness.
+///
+/// Because there is no source to recompile, hot-reloading is not supported for these shaders.
+#[proc_macro]
+pub fn include_spirv(tokens: TokenStream) -> TokenStream {
+    let IncludeSpirv(output) = parse_macro_input!(tokens as IncludeSpirv);
+    output.expand().into()
 }
 
 /// Compile inline GLSL source
@@ -138,6 +773,69 @@ pub fn include_glsl(tokens: TokenStream) -> TokenStream {
 /// See the [`include_glsl!`] macro for a list of compile options.
 #[proc_macro]
 pub fn glsl(tokens: TokenStream) -> TokenStream {
-    let Glsl(output) = parse_macro_input!(tokens as Glsl);
+    let Glsl(variants) = parse_macro_input!(tokens as Glsl);
+    variants.expand(Output::expand).into()
+}
+
+/// Compile inline HLSL source
+///
+/// ```
+/// use vk_shader_macros::{hlsl, ShaderData};
+/// static COMP: ShaderData = hlsl! {
+///     kind: comp, entry: "CSMain",
+///     r#"
+/// RWStructuredBuffer<uint> output : register(u0);
+///
+/// [numthreads(64, 1, 1)]
+/// void CSMain(uint3 id : SV_DispatchThreadID) {
+///     output[id.x] = id.x;
+/// }
+/// "#
+/// };
+/// ```
+///
+/// Includes are resolved relative to the current file.
+///
+/// # Options
+///
+/// See the [`include_hlsl!`] macro for a list of compile options. As with [`include_hlsl!`], the
+/// `entry` option is required.
+#[proc_macro]
+pub fn hlsl(tokens: TokenStream) -> TokenStream {
+    let Hlsl(Glsl(variants)) = parse_macro_input!(tokens as Hlsl);
+    variants.expand(Output::expand).into()
+}
+
+/// Assemble inline SPIR-V assembly
+///
+/// ```
+/// use vk_shader_macros::{spvasm, ShaderData};
+/// static COMP: ShaderData = spvasm! {
+///     target: vulkan1_1,
+///     r#"
+///                OpCapability Shader
+///                OpMemoryModel Logical GLSL450
+///                OpEntryPoint GLCompute %main "main"
+///                OpExecutionMode %main LocalSize 1 1 1
+///        %void = OpTypeVoid
+///          %fn = OpTypeFunction %void
+///        %main = OpFunction %void None %fn
+///       %entry = OpLabel
+///                OpReturn
+///                OpFunctionEnd
+/// "#
+/// };
+/// ```
+///
+/// The assembly syntax is the one used by
+/// [SPIRV-Tools](https://github.com/KhronosGroup/SPIRV-Tools/blob/main/docs/syntax.md).
+/// Assembler errors are reported on the source literal.
+///
+/// # Options
+///
+/// Only the `target` option from [`include_glsl!`] affects assembly.
+#[proc_macro]
+pub fn spvasm(tokens: TokenStream) -> TokenStream {
+    let Spvasm(output) = parse_macro_input!(tokens as Spvasm);
     output.expand().into()
 }
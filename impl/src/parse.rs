@@ -1,39 +1,75 @@
-use crate::build::{BuildOptions, Builder, Output};
-use crate::IncludeGlsl;
-use proc_macro2::TokenStream;
+use crate::build::{
+    vulkan_version, BuildOptions, Builder, GlslProfile, OptimizationLevel, Output, ResourceKind,
+    ShaderKind, SourceLanguage, TargetEnv, Warnings,
+};
+use crate::config;
+use crate::{IncludeGlsl, IncludeGlslDir, IncludeGlslPermutations, IncludeGlslStages};
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
-use shaderc::ShaderKind;
 use std::borrow::Cow;
-use std::fs;
+use std::path::Path;
 use std::time::SystemTime;
+use std::{env, fs};
 use syn::parse::{Parse, ParseStream};
-use syn::{Ident, LitInt, LitStr, Token};
+use syn::{parenthesized, Ident, Lit, LitBool, LitByteStr, LitInt, LitStr, Token};
 
 impl Output {
-    pub fn expand(self) -> TokenStream {
+    pub fn expand(&self) -> TokenStream {
+        let hot_reloading_data = if cfg!(feature = "hot-reloading") {
+            quote!(hot_reloading: None,)
+        } else {
+            TokenStream::default()
+        };
+
+        self.shader_data(hot_reloading_data)
+    }
+
+    /// Expression constructing the `ShaderData` for this output
+    fn shader_data(&self, hot_reloading_data: TokenStream) -> TokenStream {
         let Self {
             sources,
             spv,
+            entry_point,
+            warnings,
             #[cfg(feature = "reflection")]
             entry_points,
         } = self;
 
-        let hot_reloading_data = if cfg!(feature = "hot-reloading") {
-            quote!(hot_reloading: None,)
-        } else {
-            TokenStream::default()
-        };
+        let mut entry_point = entry_point.clone().into_bytes();
+        entry_point.push(0);
+        let entry_point = LitByteStr::new(&entry_point, Span::call_site());
 
         #[cfg(feature = "reflection")]
-        let reflection_data = reflection_data(&entry_points);
+        let reflection_data = reflection_data(entry_points);
         #[cfg(not(feature = "reflection"))]
         let reflection_data = TokenStream::default();
 
+        // recompile when the configuration changes
+        let config = config::path()
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned());
+        // the invoking file of inline shaders may be unavailable, see `call_site_file`
+        let sources = sources.iter().filter(|source| Path::new(source).is_file());
+        let env_vars = config::tracked_env_vars();
+
         quote!(
             {
                 #({ const _FORCE_DEP: &[u8] = include_bytes!(#sources); })*
+                #({ const _FORCE_DEP: &[u8] = include_bytes!(#config); })*
+                #({ const _FORCE_DEP: Option<&str> = option_env!(#env_vars); })*
+                // report shader warnings through the deprecation lint, the only way to emit
+                // warnings from a proc macro on stable
+                #({
+                    #[deprecated(note = #warnings)]
+                    struct ShaderWarning;
+                    const _: ShaderWarning = ShaderWarning;
+                })*
                 ::vk_shader_macros::ShaderData {
                     compile_time_spv: &[#(#spv),*],
+                    entry_point: match ::std::ffi::CStr::from_bytes_with_nul(#entry_point) {
+                        Ok(entry_point) => entry_point,
+                        Err(_) => panic!("invalid entry point name"),
+                    },
                     #hot_reloading_data
                     #reflection_data
                 }
@@ -42,9 +78,123 @@ impl Output {
     }
 }
 
-impl Parse for BuildOptions {
+/// Compile options of a macro invocation
+pub(crate) struct Options {
+    pub build: BuildOptions,
+    /// `define_if` options, as the `cfg` predicate and the definition enabled by it
+    #[allow(clippy::type_complexity)]
+    pub define_if: Vec<(TokenStream, (Cow<'static, str>, Option<Cow<'static, str>>))>,
+    /// Names of the defines of the configuration file not overridden by the invocation
+    config_defines: Vec<Cow<'static, str>>,
+}
+
+impl Options {
+    fn new(build: BuildOptions) -> Self {
+        let config_defines = build
+            .definitions
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        Self {
+            build,
+            define_if: Vec::new(),
+            config_defines,
+        }
+    }
+
+    /// Options of an invocation without compile options
+    pub fn for_invocation() -> syn::Result<Self> {
+        Ok(Self::new(BuildOptions::for_invocation()?))
+    }
+
+    /// Remove the defines of the configuration file, keeping the ones given to the invocation
+    #[cfg(feature = "wgsl")]
+    pub fn remove_config_defines(&mut self) {
+        let config_defines = &self.config_defines;
+        self.build
+            .definitions
+            .to_mut()
+            .retain(|(name, _)| !config_defines.contains(name));
+    }
+
+    /// Compile a variant for each combination of the `define_if` predicates
+    pub fn compile<T>(
+        &self,
+        mut compile: impl FnMut(BuildOptions) -> syn::Result<T>,
+    ) -> syn::Result<CfgVariants<T>> {
+        const MAX_PREDICATES: usize = 8;
+
+        if self.define_if.len() > MAX_PREDICATES {
+            return Err(syn::Error::new_spanned(
+                &self.define_if[MAX_PREDICATES].0,
+                format!("at most {MAX_PREDICATES} `define_if` options are supported"),
+            ));
+        }
+
+        let variants = (0..1_u32 << self.define_if.len())
+            .map(|bits| {
+                let mut options = self.build.clone();
+                for (i, (_, definition)) in self.define_if.iter().enumerate() {
+                    if bits & (1 << i) != 0 {
+                        let definitions = options.definitions.to_mut();
+                        definitions.retain(|(x, _)| *x != definition.0);
+                        definitions.push(definition.clone());
+                    }
+                }
+                compile(options)
+            })
+            .collect::<syn::Result<_>>()?;
+
+        Ok(CfgVariants {
+            predicates: self.define_if.iter().map(|(x, _)| x.clone()).collect(),
+            variants,
+        })
+    }
+
+    /// Options of a macro that does not support `define_if`
+    pub fn without_define_if(self) -> syn::Result<BuildOptions> {
+        match self.define_if.first() {
+            Some((predicate, _)) => Err(syn::Error::new_spanned(
+                predicate,
+                "`define_if` is not supported by this macro",
+            )),
+            None => Ok(self.build),
+        }
+    }
+}
+
+/// Shaders compiled for each combination of `define_if` predicates,
+/// selected with `cfg!` in the calling crate
+pub(crate) struct CfgVariants<T> {
+    predicates: Vec<TokenStream>,
+    /// Indexed by the bits of the enabled predicates
+    variants: Vec<T>,
+}
+
+impl<T> CfgVariants<T> {
+    /// Expression evaluating to the variant of the enabled predicates
+    pub fn expand(&self, mut expand: impl FnMut(&T) -> TokenStream) -> TokenStream {
+        let mut variants = self.variants.iter().map(&mut expand).collect::<Vec<_>>();
+        // merge the variants pairwise, starting with the last predicate,
+        // which selects between the two halves
+        for predicate in self.predicates.iter().rev() {
+            let half = variants.len() / 2;
+            let enabled = variants.split_off(half);
+            variants = variants
+                .into_iter()
+                .zip(enabled)
+                .map(|(disabled, enabled)| {
+                    quote!(if cfg!(#predicate) { #enabled } else { #disabled })
+                })
+                .collect();
+        }
+        variants.pop().unwrap()
+    }
+}
+
+impl Parse for Options {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut out = Self::default();
+        let mut out = Self::new(config::defaults()?);
 
         while input.peek(Ident) {
             let key = input.parse::<Ident>()?;
@@ -54,22 +204,54 @@ impl Parse for BuildOptions {
 
                     let value = input.parse::<Ident>()?;
                     if let Some(kind) = crate::build::extension_kind(&value.to_string()) {
-                        out.kind = Some(kind);
+                        out.build.kind = Some(kind);
+                    } else {
+                        return Err(syn::Error::new(value.span(), "unknown shader kind"));
+                    }
+                }
+                "default_kind" => {
+                    input.parse::<Token![:]>()?;
+
+                    let value = input.parse::<Ident>()?;
+                    if let Some(kind) = crate::build::extension_kind(&value.to_string())
+                        .and_then(crate::build::default_kind)
+                    {
+                        out.build.default_kind = Some(kind);
                     } else {
                         return Err(syn::Error::new(value.span(), "unknown shader kind"));
                     }
                 }
+                "kind_dir" => {
+                    input.parse::<Token![:]>()?;
+
+                    let name = input.parse::<LitStr>()?;
+                    input.parse::<Token![=>]>()?;
+                    let value = input.parse::<Ident>()?;
+                    let kind = crate::build::extension_kind(&value.to_string())
+                        .ok_or_else(|| syn::Error::new(value.span(), "unknown shader kind"))?;
+                    let kind_dirs = out.build.kind_dirs.to_mut();
+                    kind_dirs.retain(|(x, _)| *x != name.value());
+                    kind_dirs.push((Cow::Owned(name.value()), kind));
+                }
                 "version" => {
                     input.parse::<Token![:]>()?;
 
                     let value = input.parse::<LitInt>()?;
-                    out.version = Some(value.base10_parse()?);
+                    out.build.version = Some(value.base10_parse()?);
+                    if input.peek(Ident) && !input.peek2(Token![:]) {
+                        out.build.profile = parse_profile(input)?;
+                    }
+                }
+                "profile" => {
+                    input.parse::<Token![:]>()?;
+
+                    out.build.profile = parse_profile(input)?;
                 }
                 "strip" => {
-                    out.debug = false;
+                    out.build.debug = false;
                 }
                 "debug" => {
-                    out.debug = true;
+                    out.build.debug = true;
                 }
                 "define" => {
                     input.parse::<Token![:]>()?;
@@ -78,18 +260,38 @@ impl Parse for BuildOptions {
                     let value = if input.peek(Token![,]) || input.is_empty() {
                         None
                     } else {
-                        Some(Cow::Owned(input.parse::<LitStr>()?.value()))
+                        Some(Cow::Owned(define_value(input)?))
                     };
-                    out.definitions
-                        .to_mut()
-                        .push((Cow::Owned(name.to_string()), value));
+                    // overrides a define of the configuration file
+                    out.config_defines.retain(|x| name != x);
+                    let definitions = out.build.definitions.to_mut();
+                    definitions.retain(|(x, _)| name != x);
+                    definitions.push((Cow::Owned(name.to_string()), value));
+                }
+                "define_if" => {
+                    input.parse::<Token![:]>()?;
+
+                    let content;
+                    parenthesized!(content in input);
+                    let predicate = content.parse::<TokenStream>()?;
+                    if predicate.is_empty() {
+                        return Err(content.error("expected a `cfg` predicate"));
+                    }
+                    let name = input.parse::<Ident>()?;
+                    let value = if input.peek(Token![,]) || input.is_empty() {
+                        None
+                    } else {
+                        Some(Cow::Owned(define_value(input)?))
+                    };
+                    out.define_if
+                        .push((predicate, (Cow::Owned(name.to_string()), value)));
                 }
                 "optimize" => {
                     input.parse::<Token![:]>()?;
 
                     let value = input.parse::<Ident>()?;
                     if let Some(level) = optimization_level(&value.to_string()) {
-                        out.optimization = level;
+                        out.build.optimization = level;
                     } else {
                         return Err(
                             syn::Error::new(value.span(), "unknown optimization level").into()
@@ -100,12 +302,125 @@ impl Parse for BuildOptions {
                     input.parse::<Token![:]>()?;
 
                     let value = input.parse::<Ident>()?;
-                    if let Some(version) = target(&value.to_string()) {
-                        out.target_version = version as u32;
+                    if let Some((env, version)) = target(&value.to_string()) {
+                        out.build.target_env = env;
+                        out.build.target_version = version;
                     } else {
                         return Err(syn::Error::new(value.span(), "unknown target").into());
                     }
                 }
+                "spirv" => {
+                    input.parse::<Token![:]>()?;
+
+                    let value = input.parse::<LitInt>()?;
+                    if let Some(version) = spirv_version(&value.to_string()) {
+                        out.build.spirv_version = Some(version);
+                    } else {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "unknown SPIR-V version, expected `1_0` to `1_6`",
+                        ));
+                    }
+                }
+                "entry" => {
+                    input.parse::<Token![:]>()?;
+
+                    let value = input.parse::<LitStr>()?;
+                    if value.value().contains('\0') {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "entry point name must not contain nul bytes",
+                        ));
+                    }
+                    out.build.entry_point = Some(Cow::Owned(value.value()));
+                }
+                "warnings" => {
+                    input.parse::<Token![:]>()?;
+
+                    let value = input.parse::<Ident>()?;
+                    if let Some(level) = warnings_level(&value.to_string()) {
+                        out.build.warnings = level;
+                    } else {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "unknown warnings level, expected `deny`, `warn` or `allow`",
+                        ));
+                    }
+                }
+                "auto_bind" => {
+                    input.parse::<Token![:]>()?;
+
+                    out.build.auto_bind_uniforms = input.parse::<LitBool>()?.value;
+                }
+                "auto_map_locations" => {
+                    input.parse::<Token![:]>()?;
+
+                    out.build.auto_map_locations = input.parse::<LitBool>()?.value;
+                }
+                "binding_base" => {
+                    input.parse::<Token![:]>()?;
+
+                    let kind = input.parse::<Ident>()?;
+                    let kind = resource_kind(&kind.to_string())
+                        .ok_or_else(|| syn::Error::new(kind.span(), "unknown resource kind"))?;
+                    let base = input.parse::<LitInt>()?.base10_parse()?;
+                    let bases = out.build.binding_bases.to_mut();
+                    bases.retain(|&(x, _)| x != kind);
+                    bases.push((kind, base));
+                }
+                "set_binding_shift" => {
+                    input.parse::<Token![:]>()?;
+
+                    let set = input.parse::<LitInt>()?.base10_parse()?;
+                    let shift = input.parse::<LitInt>()?.base10_parse()?;
+                    let shifts = out.build.set_binding_shifts.to_mut();
+                    shifts.retain(|&(x, _)| x != set);
+                    shifts.push((set, shift));
+                }
+                "prelude" => {
+                    input.parse::<Token![:]>()?;
+
+                    out.build.prelude = if input.peek(Ident) {
+                        let value = input.parse::<Ident>()?;
+                        if value != "none" {
+                            return Err(syn::Error::new(value.span(), "expected a path or `none`"));
+                        }
+                        None
+                    } else {
+                        let value = input.parse::<LitStr>()?;
+                        Some(Cow::Owned(
+                            prelude_path(&value.value())
+                                .map_err(|e| syn::Error::new(value.span(), e))?,
+                        ))
+                    };
+                }
+                "include_path" => {
+                    input.parse::<Token![:]>()?;
+
+                    let value = input.parse::<LitStr>()?;
+                    let path = include_path(&value.value())
+                        .map_err(|e| syn::Error::new(value.span(), e))?;
+                    out.build.include_paths.to_mut().push(Cow::Owned(path));
+                }
+                "virtual_include" => {
+                    input.parse::<Token![:]>()?;
+
+                    let name = input.parse::<LitStr>()?;
+                    input.parse::<Token![=>]>()?;
+                    let content = input.parse::<LitStr>()?;
+                    if out
+                        .build
+                        .virtual_includes
+                        .iter()
+                        .any(|(existing, _)| *existing == name.value())
+                    {
+                        return Err(syn::Error::new(name.span(), "duplicate virtual include"));
+                    }
+                    out.build
+                        .virtual_includes
+                        .to_mut()
+                        .push((Cow::Owned(name.value()), Cow::Owned(content.value())));
+                }
                 _ => {
                     return Err(syn::Error::new(key.span(), "unknown shader compile option").into());
                 }
@@ -118,20 +433,172 @@ impl Parse for BuildOptions {
             }
         }
 
+        if out.build.profile != GlslProfile::None && out.build.version.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "a GLSL profile can only be forced along with a `version`",
+            ));
+        }
+
+        config::apply_env(&mut out.build)?;
+        out.build.add_project_include_paths()?;
+        Ok(out)
+    }
+}
+
+impl BuildOptions {
+    /// Options of an invocation without compile options
+    pub fn for_invocation() -> syn::Result<Self> {
+        let mut out = config::defaults()?;
+        config::apply_env(&mut out)?;
+        out.add_project_include_paths()?;
         Ok(out)
     }
+
+    /// Project-wide include paths are searched after the ones of the invocation,
+    /// followed by the crate root. Shader libraries of dependencies are added as well.
+    fn add_project_include_paths(&mut self) -> syn::Result<()> {
+        for path in config::include_paths()? {
+            self.include_paths.to_mut().push(Cow::Owned(path));
+        }
+        if let Some(paths) = env::var_os(INCLUDE_PATH_VAR) {
+            for path in env::split_paths(&paths) {
+                let path = include_path(&path.to_string_lossy()).map_err(|e| {
+                    syn::Error::new(Span::call_site(), format!("{INCLUDE_PATH_VAR}: {e}"))
+                })?;
+                self.include_paths.to_mut().push(Cow::Owned(path));
+            }
+        }
+        let manifest_dir = include_path(".").map_err(|e| syn::Error::new(Span::call_site(), e))?;
+        self.include_paths.to_mut().push(Cow::Owned(manifest_dir));
+        for (name, dir) in config::shader_libraries() {
+            self.shader_libraries
+                .to_mut()
+                .push((Cow::Owned(name), Cow::Owned(dir)));
+        }
+        Ok(())
+    }
+}
+
+/// Absolute path of a prelude, relative paths are relative to the crate root
+pub(crate) fn prelude_path(path: &str) -> Result<String, String> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = Path::new(&manifest_dir).join(path);
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("prelude {}: {e}", path.display()))?;
+    path.into_os_string()
+        .into_string()
+        .map_err(|_| "non-unicode prelude path".to_owned())
+}
+
+/// String define value, which is passed through verbatim
+///
+/// Line breaks would end the `#define` directive, and a trailing backslash would continue it on
+/// the next line.
+pub(crate) fn define_string(value: String) -> Result<String, String> {
+    if value.contains(['\n', '\r', '\0']) {
+        return Err("define value must not contain line breaks or nul bytes".to_owned());
+    }
+    if value.ends_with('\\') {
+        return Err("define value must not end with a backslash".to_owned());
+    }
+    Ok(value)
+}
+
+/// Float define value from its decimal digits
+///
+/// Values without a fractional part or exponent, like `1f32`, get one, as GLSL would read them
+/// as integers.
+pub(crate) fn define_float(digits: &str) -> String {
+    if digits.contains(['.', 'e', 'E']) {
+        digits.to_owned()
+    } else {
+        format!("{digits}.0")
+    }
+}
+
+/// Value of a `define` option, formatted as GLSL
+///
+/// Accepts string literals, which are passed through verbatim, and bool, integer and float
+/// literals, which may be negated.
+fn define_value(input: ParseStream) -> syn::Result<String> {
+    let negative = input.parse::<Option<Token![-]>>()?;
+    let lit = input.parse::<Lit>()?;
+    let value = match &lit {
+        Lit::Str(value) if negative.is_none() => {
+            return define_string(value.value()).map_err(|e| syn::Error::new(lit.span(), e));
+        }
+        Lit::Bool(value) if negative.is_none() => return Ok(value.value.to_string()),
+        Lit::Int(value) => {
+            let digits = value.base10_digits();
+            match value.suffix() {
+                "" | "i32" => digits.to_owned(),
+                "u" | "u32" if negative.is_none() => format!("{digits}u"),
+                // `1f32` is an integer literal with a float suffix
+                "f32" => define_float(digits),
+                "f64" => format!("{}lf", define_float(digits)),
+                _ => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "unsupported integer suffix, expected `i32` or `u32`",
+                    ))
+                }
+            }
+        }
+        Lit::Float(value) => {
+            let suffix = match value.suffix() {
+                "" | "f32" => "",
+                "f64" => "lf",
+                _ => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "unsupported float suffix, expected `f32` or `f64`",
+                    ))
+                }
+            };
+            format!("{}{suffix}", define_float(value.base10_digits()))
+        }
+        _ => {
+            return Err(syn::Error::new(
+                lit.span(),
+                "expected a string, bool, integer or float define value",
+            ))
+        }
+    };
+    Ok(if negative.is_some() {
+        format!("-{value}")
+    } else {
+        value
+    })
+}
+
+/// Environment variable holding the project-wide include paths,
+/// separated like `PATH`, e.g. set in the `[env]` section of `.cargo/config.toml`
+pub(crate) const INCLUDE_PATH_VAR: &str = "VK_SHADER_MACROS_INCLUDE_PATH";
+
+/// Absolute path of an include directory, relative paths are relative to the crate root
+pub(crate) fn include_path(path: &str) -> Result<String, String> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = Path::new(&manifest_dir).join(path);
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("include path {}: {e}", path.display()))?;
+    if !path.is_dir() {
+        return Err(format!(
+            "include path {} is not a directory",
+            path.display()
+        ));
+    }
+    path.into_os_string()
+        .into_string()
+        .map_err(|_| "non-unicode include path".to_owned())
 }
 
 impl ToTokens for IncludeGlsl {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
-            output:
-                Output {
-                    sources,
-                    spv,
-                    #[cfg(feature = "reflection")]
-                    entry_points,
-                },
+            output,
             builder:
                 Builder {
                     options: build_options,
@@ -140,7 +607,7 @@ impl ToTokens for IncludeGlsl {
         } = self;
 
         let hot_reloading_data = if cfg!(feature = "hot-reloading") {
-            let paths = sources.iter().map(|source| {
+            let paths = output.sources.iter().map(|source| {
                 let modified = fs::metadata(source)
                     .unwrap()
                     .modified()
@@ -164,18 +631,96 @@ impl ToTokens for IncludeGlsl {
             TokenStream::default()
         };
 
-        #[cfg(feature = "reflection")]
-        let reflection_data = reflection_data(entry_points);
-        #[cfg(not(feature = "reflection"))]
-        let reflection_data = TokenStream::default();
+        tokens.append_all(output.shader_data(hot_reloading_data))
+    }
+}
+
+impl ToTokens for IncludeGlslStages {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let stages = &self.0;
+        tokens.append_all(quote!((#(#stages,)*)))
+    }
+}
+
+impl ToTokens for IncludeGlslDir {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            visibility,
+            name,
+            shaders,
+        } = self;
+
+        let items = shaders.iter().map(|(_, item_name, shader)| {
+            quote!(pub static #item_name: ::vk_shader_macros::ShaderData = #shader;)
+        });
+        let all = shaders
+            .iter()
+            .map(|(file_name, item_name, _)| quote!((#file_name, &#item_name)));
 
         tokens.append_all(quote!(
-            {
-                #({ const _FORCE_DEP: &[u8] = include_bytes!(#sources); })*
-                ::vk_shader_macros::ShaderData {
-                    compile_time_spv: &[#(#spv),*],
-                    #hot_reloading_data
-                    #reflection_data
+            #visibility mod #name {
+                #(#items)*
+
+                pub static ALL: &[(&str, &::vk_shader_macros::ShaderData)] = &[#(#all),*];
+            }
+        ))
+    }
+}
+
+impl ToTokens for IncludeGlslPermutations {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            visibility,
+            name,
+            defines,
+            permutations,
+        } = self;
+
+        let flags = defines.iter().enumerate().map(|(i, define)| {
+            let bit = 1_u32 << i;
+            let doc = format!("Compile with `{define}` defined");
+            quote!(
+                #[doc = #doc]
+                pub const #define: Self = Self(#bit);
+            )
+        });
+        let count = permutations.len();
+
+        tokens.append_all(quote!(
+            #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+            #visibility struct #name(u32);
+
+            impl #name {
+                #(#flags)*
+
+                /// Bits of the set preprocessor macros
+                pub const fn bits(self) -> u32 {
+                    self.0
+                }
+
+                /// Whether all macros set in `other` are also set in `self`
+                pub const fn contains(self, other: Self) -> bool {
+                    self.0 & other.0 == other.0
+                }
+
+                /// Shader compiled with exactly the macros set in `self`
+                pub fn shader(self) -> &'static ::vk_shader_macros::ShaderData {
+                    static SHADERS: [::vk_shader_macros::ShaderData; #count] = [#(#permutations),*];
+                    &SHADERS[self.0 as usize]
+                }
+            }
+
+            impl ::std::ops::BitOr for #name {
+                type Output = Self;
+
+                fn bitor(self, other: Self) -> Self {
+                    Self(self.0 | other.0)
+                }
+            }
+
+            impl ::std::ops::BitOrAssign for #name {
+                fn bitor_assign(&mut self, other: Self) {
+                    self.0 |= other.0;
                 }
             }
         ))
@@ -185,22 +730,89 @@ impl ToTokens for IncludeGlsl {
 impl ToTokens for BuildOptions {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
+            source_language,
             kind,
+            default_kind,
+            kind_dirs,
             version,
+            profile,
             debug,
             definitions,
             optimization,
+            target_env,
             target_version,
-            ..
+            spirv_version,
+            entry_point,
+            prelude,
+            include_paths,
+            shader_libraries,
+            virtual_includes,
+            warnings,
+            auto_bind_uniforms,
+            auto_map_locations,
+            binding_bases,
+            set_binding_shifts,
         } = self;
 
+        let source_language = match source_language {
+            SourceLanguage::Glsl => quote!(::vk_shader_macros::SourceLanguage::Glsl),
+            SourceLanguage::Hlsl => quote!(::vk_shader_macros::SourceLanguage::Hlsl),
+            #[cfg(feature = "wgsl")]
+            SourceLanguage::Wgsl => quote!(::vk_shader_macros::SourceLanguage::Wgsl),
+        };
         let kind = kind_extension(*kind);
+        let default_kind = kind_extension(*default_kind);
+        let kind_dirs = kind_dirs.iter().map(|(name, kind)| {
+            let kind = Ident::new(&format!("{kind:?}"), Span::call_site());
+            quote!((
+                ::std::borrow::Cow::Borrowed(#name),
+                ::vk_shader_macros::ShaderKind::#kind,
+            ))
+        });
         let version = if let Some(version) = version.as_ref() {
             quote!(Some(#version))
         } else {
             quote!(None)
         };
         let optimization = serialize_optimization_level(*optimization);
+        let include_paths = include_paths.iter();
+        let shader_libraries = shader_libraries.iter().map(|(name, dir)| {
+            quote!((
+                ::std::borrow::Cow::Borrowed(#name),
+                ::std::borrow::Cow::Borrowed(#dir),
+            ))
+        });
+        let prelude = if let Some(prelude) = prelude.as_ref() {
+            quote!(Some(::std::borrow::Cow::Borrowed(#prelude)))
+        } else {
+            quote!(None)
+        };
+        let profile = Ident::new(&format!("{profile:?}"), Span::call_site());
+        let target_env = Ident::new(&format!("{target_env:?}"), Span::call_site());
+        let spirv_version = if let Some((major, minor)) = spirv_version {
+            quote!(Some((#major, #minor)))
+        } else {
+            quote!(None)
+        };
+        let warnings = Ident::new(&format!("{warnings:?}"), Span::call_site());
+        let binding_bases = binding_bases.iter().map(|(kind, base)| {
+            let kind = Ident::new(&format!("{kind:?}"), Span::call_site());
+            quote!((::vk_shader_macros::ResourceKind::#kind, #base))
+        });
+        let set_binding_shifts = set_binding_shifts
+            .iter()
+            .map(|(set, shift)| quote!((#set, #shift)));
+        let virtual_includes = virtual_includes.iter().map(|(name, content)| {
+            quote!((
+                ::std::borrow::Cow::Borrowed(#name),
+                ::std::borrow::Cow::Borrowed(#content),
+            ))
+        });
+        let entry_point = if let Some(entry_point) = entry_point.as_ref() {
+            quote!(Some(::std::borrow::Cow::Borrowed(#entry_point)))
+        } else {
+            quote!(None)
+        };
         #[allow(unused_variables)] // false positive? with `quote!`
         let definitions = definitions.iter().map(|(a, b)| {
             let b = if let Some(b) = b.as_ref() {
@@ -212,12 +824,30 @@ impl ToTokens for BuildOptions {
         });
 
         tokens.append_all(quote!(::vk_shader_macros::BuildOptions {
+            source_language: #source_language,
             kind: #kind,
+            default_kind: #default_kind,
+            kind_dirs: ::std::borrow::Cow::Borrowed(&[#(#kind_dirs),*]),
             version: #version,
+            profile: ::vk_shader_macros::GlslProfile::#profile,
             debug: #debug,
             definitions: ::std::borrow::Cow::Borrowed(&[#(#definitions),*]),
             optimization: #optimization,
+            target_env: ::vk_shader_macros::TargetEnv::#target_env,
             target_version: #target_version,
+            spirv_version: #spirv_version,
+            entry_point: #entry_point,
+            prelude: #prelude,
+            include_paths: ::std::borrow::Cow::Borrowed(&[
+                #(::std::borrow::Cow::Borrowed(#include_paths)),*
+            ]),
+            shader_libraries: ::std::borrow::Cow::Borrowed(&[#(#shader_libraries),*]),
+            virtual_includes: ::std::borrow::Cow::Borrowed(&[#(#virtual_includes),*]),
+            warnings: ::vk_shader_macros::Warnings::#warnings,
+            auto_bind_uniforms: #auto_bind_uniforms,
+            auto_map_locations: #auto_map_locations,
+            binding_bases: ::std::borrow::Cow::Borrowed(&[#(#binding_bases),*]),
+            set_binding_shifts: ::std::borrow::Cow::Borrowed(&[#(#set_binding_shifts),*]),
         }))
     }
 }
@@ -230,7 +860,7 @@ pub(crate) fn kind_extension(shader_kind: Option<ShaderKind>) -> syn::Expr {
     }
     let shader_kind = shader_kind.unwrap();
 
-    use shaderc::ShaderKind::*;
+    use ShaderKind::*;
     s += match shader_kind {
         Vertex => "Vertex",
         Fragment => "Fragment",
@@ -247,47 +877,150 @@ pub(crate) fn kind_extension(shader_kind: Option<ShaderKind>) -> syn::Expr {
         Callable => "Callable",
         Task => "Task",
         Mesh => "Mesh",
-        _ => {
-            return syn::parse_str("None").unwrap();
-        }
+        InferFromSource => "InferFromSource",
+        DefaultVertex => "DefaultVertex",
+        DefaultFragment => "DefaultFragment",
+        DefaultCompute => "DefaultCompute",
+        DefaultGeometry => "DefaultGeometry",
+        DefaultTessControl => "DefaultTessControl",
+        DefaultTessEvaluation => "DefaultTessEvaluation",
+        DefaultRayGeneration => "DefaultRayGeneration",
+        DefaultAnyHit => "DefaultAnyHit",
+        DefaultClosestHit => "DefaultClosestHit",
+        DefaultMiss => "DefaultMiss",
+        DefaultIntersection => "DefaultIntersection",
+        DefaultCallable => "DefaultCallable",
+        DefaultTask => "DefaultTask",
+        DefaultMesh => "DefaultMesh",
     };
     s += ")";
     syn::parse_str(&s).unwrap()
 }
 
-pub(crate) fn serialize_optimization_level(level: shaderc::OptimizationLevel) -> syn::Expr {
+/// Name of the item generated for a file by `include_glsl_dir!`
+pub(crate) fn item_name(file_name: &str) -> Ident {
+    let mut name = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    Ident::new(&name, Span::call_site())
+}
+
+/// Preprocessor macro defined when compiling a stage with `include_glsl_stages!`
+pub(crate) fn stage_define(shader_kind: ShaderKind) -> Option<&'static str> {
+    use ShaderKind::*;
+    Some(match shader_kind {
+        Vertex => "VERTEX",
+        Fragment => "FRAGMENT",
+        Compute => "COMPUTE",
+        Geometry => "GEOMETRY",
+        TessControl => "TESS_CONTROL",
+        TessEvaluation => "TESS_EVALUATION",
+        RayGeneration => "RAY_GENERATION",
+        AnyHit => "ANY_HIT",
+        ClosestHit => "CLOSEST_HIT",
+        Miss => "MISS",
+        Intersection => "INTERSECTION",
+        Callable => "CALLABLE",
+        Task => "TASK",
+        Mesh => "MESH",
+        _ => return None,
+    })
+}
+
+pub(crate) fn serialize_optimization_level(level: OptimizationLevel) -> syn::Expr {
     match level {
-        shaderc::OptimizationLevel::Zero => {
+        OptimizationLevel::Zero => {
             syn::parse_str("::vk_shader_macros::OptimizationLevel::Zero").unwrap()
         }
-        shaderc::OptimizationLevel::Size => {
+        OptimizationLevel::Size => {
             syn::parse_str("::vk_shader_macros::OptimizationLevel::Size").unwrap()
         }
-        shaderc::OptimizationLevel::Performance => {
+        OptimizationLevel::Performance => {
             syn::parse_str("::vk_shader_macros::OptimizationLevel::Performance").unwrap()
         }
     }
 }
 
-pub(crate) fn optimization_level(level: &str) -> Option<shaderc::OptimizationLevel> {
+pub(crate) fn optimization_level(level: &str) -> Option<OptimizationLevel> {
     match level {
-        "zero" => Some(shaderc::OptimizationLevel::Zero),
-        "size" => Some(shaderc::OptimizationLevel::Size),
-        "performance" => Some(shaderc::OptimizationLevel::Performance),
+        "zero" => Some(OptimizationLevel::Zero),
+        "size" => Some(OptimizationLevel::Size),
+        "performance" => Some(OptimizationLevel::Performance),
         _ => None,
     }
 }
 
-pub(crate) fn target(s: &str) -> Option<shaderc::EnvVersion> {
+pub(crate) fn target(s: &str) -> Option<(TargetEnv, u32)> {
+    Some(match s {
+        "vulkan" | "vulkan1_0" => (TargetEnv::Vulkan, vulkan_version(0)),
+        "vulkan1_1" => (TargetEnv::Vulkan, vulkan_version(1)),
+        "vulkan1_2" => (TargetEnv::Vulkan, vulkan_version(2)),
+        "vulkan1_3" => (TargetEnv::Vulkan, vulkan_version(3)),
+        "vulkan1_4" => (TargetEnv::Vulkan, vulkan_version(4)),
+        "opengl" | "opengl4_5" => (TargetEnv::OpenGL, 450),
+        _ => return None,
+    })
+}
+
+fn parse_profile(input: ParseStream) -> syn::Result<GlslProfile> {
+    let value = input.parse::<Ident>()?;
+    Ok(match value.to_string().as_str() {
+        "core" => GlslProfile::Core,
+        "compatibility" => GlslProfile::Compatibility,
+        "es" => GlslProfile::Es,
+        _ => {
+            return Err(syn::Error::new(
+                value.span(),
+                "unknown GLSL profile, expected `core`, `compatibility` or `es`",
+            ))
+        }
+    })
+}
+
+pub(crate) fn warnings_level(s: &str) -> Option<Warnings> {
     Some(match s {
-        "vulkan" | "vulkan1_0" => shaderc::EnvVersion::Vulkan1_0,
-        "vulkan1_1" => shaderc::EnvVersion::Vulkan1_1,
-        "vulkan1_2" => shaderc::EnvVersion::Vulkan1_2,
-        "vulkan1_3" => shaderc::EnvVersion::Vulkan1_3,
+        "deny" => Warnings::Deny,
+        "warn" => Warnings::Warn,
+        "allow" => Warnings::Allow,
         _ => return None,
     })
 }
 
+pub(crate) fn resource_kind(s: &str) -> Option<ResourceKind> {
+    Some(match s {
+        "texture" => ResourceKind::Texture,
+        "sampler" => ResourceKind::Sampler,
+        "image" => ResourceKind::Image,
+        "uniform_buffer" => ResourceKind::UniformBuffer,
+        "storage_buffer" => ResourceKind::StorageBuffer,
+        _ => return None,
+    })
+}
+
+/// SPIR-V version written like `1_4`
+pub(crate) fn spirv_version(s: &str) -> Option<(u8, u8)> {
+    match s {
+        "1_0" => Some((1, 0)),
+        "1_1" => Some((1, 1)),
+        "1_2" => Some((1, 2)),
+        "1_3" => Some((1, 3)),
+        "1_4" => Some((1, 4)),
+        "1_5" => Some((1, 5)),
+        "1_6" => Some((1, 6)),
+        _ => None,
+    }
+}
+
 #[cfg(feature = "reflection")]
 fn reflection_data(entry_points: &Vec<spirq::entry_point::EntryPoint>) -> TokenStream {
     use spirq::ty::{ScalarType, Type};
@@ -1,23 +1,239 @@
-use shaderc::Result;
 use std::borrow::Cow;
+#[cfg(feature = "shaderc")]
 use std::cell::RefCell;
+use std::fmt;
+use std::fs;
+#[cfg(feature = "shaderc")]
+use std::mem;
 use std::path::{Path, PathBuf};
+#[cfg(feature = "shaderc")]
 use std::sync::OnceLock;
-use std::{env, fs, mem, str};
+
+#[cfg(not(any(feature = "shaderc", feature = "naga-glsl")))]
+compile_error!("either the `shaderc` or the `naga-glsl` feature must be enabled");
+
+/// Error produced while compiling a shader
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "shaderc")]
+impl From<shaderc::Error> for Error {
+    fn from(error: shaderc::Error) -> Self {
+        Self(error.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
 
 pub struct Output {
     #[allow(dead_code)]
     pub sources: Vec<String>,
     pub spv: Vec<u32>,
     #[allow(dead_code)]
+    pub entry_point: String,
+    /// Warnings of the compiler, when [`Warnings::Warn`] is used
+    #[allow(dead_code)]
+    pub warnings: Vec<String>,
+    #[allow(dead_code)]
     #[cfg(feature = "reflection")]
     pub entry_points: Vec<spirq::entry_point::EntryPoint>,
 }
 
+/// Kind of shader, mirroring `shaderc::ShaderKind`
+///
+/// The `Default*` kinds fall back to the given kind when the source has no `#pragma shader_stage`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+// kinds inferred from the source are only supported by shaderc
+#[cfg_attr(not(feature = "shaderc"), allow(dead_code))]
+pub enum ShaderKind {
+    Vertex,
+    Fragment,
+    Compute,
+    Geometry,
+    TessControl,
+    TessEvaluation,
+    InferFromSource,
+    DefaultVertex,
+    DefaultFragment,
+    DefaultCompute,
+    DefaultGeometry,
+    DefaultTessControl,
+    DefaultTessEvaluation,
+    SpirvAssembly,
+    RayGeneration,
+    AnyHit,
+    ClosestHit,
+    Miss,
+    Intersection,
+    Callable,
+    DefaultRayGeneration,
+    DefaultAnyHit,
+    DefaultClosestHit,
+    DefaultMiss,
+    DefaultIntersection,
+    DefaultCallable,
+    Task,
+    Mesh,
+    DefaultTask,
+    DefaultMesh,
+}
+
+#[cfg(feature = "shaderc")]
+impl From<ShaderKind> for shaderc::ShaderKind {
+    fn from(kind: ShaderKind) -> Self {
+        use ShaderKind::*;
+        match kind {
+            Vertex => Self::Vertex,
+            Fragment => Self::Fragment,
+            Compute => Self::Compute,
+            Geometry => Self::Geometry,
+            TessControl => Self::TessControl,
+            TessEvaluation => Self::TessEvaluation,
+            InferFromSource => Self::InferFromSource,
+            DefaultVertex => Self::DefaultVertex,
+            DefaultFragment => Self::DefaultFragment,
+            DefaultCompute => Self::DefaultCompute,
+            DefaultGeometry => Self::DefaultGeometry,
+            DefaultTessControl => Self::DefaultTessControl,
+            DefaultTessEvaluation => Self::DefaultTessEvaluation,
+            SpirvAssembly => Self::SpirvAssembly,
+            RayGeneration => Self::RayGeneration,
+            AnyHit => Self::AnyHit,
+            ClosestHit => Self::ClosestHit,
+            Miss => Self::Miss,
+            Intersection => Self::Intersection,
+            Callable => Self::Callable,
+            DefaultRayGeneration => Self::DefaultRayGeneration,
+            DefaultAnyHit => Self::DefaultAnyHit,
+            DefaultClosestHit => Self::DefaultClosestHit,
+            DefaultMiss => Self::DefaultMiss,
+            DefaultIntersection => Self::DefaultIntersection,
+            DefaultCallable => Self::DefaultCallable,
+            Task => Self::Task,
+            Mesh => Self::Mesh,
+            DefaultTask => Self::DefaultTask,
+            DefaultMesh => Self::DefaultMesh,
+        }
+    }
+}
+
+/// Optimization level, mirroring `shaderc::OptimizationLevel`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OptimizationLevel {
+    Zero,
+    Size,
+    Performance,
+}
+
+#[cfg(feature = "shaderc")]
+impl From<OptimizationLevel> for shaderc::OptimizationLevel {
+    fn from(level: OptimizationLevel) -> Self {
+        match level {
+            OptimizationLevel::Zero => Self::Zero,
+            OptimizationLevel::Size => Self::Size,
+            OptimizationLevel::Performance => Self::Performance,
+        }
+    }
+}
+
+/// Handling of compiler warnings
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Warnings {
+    /// Fail the compilation
+    Deny,
+    /// Report the warnings, and still produce SPIR-V
+    Warn,
+    /// Ignore the warnings
+    Allow,
+}
+
+/// Kind of resource, for [`BuildOptions::binding_bases`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// Sampled image, or combined image sampler
+    Texture,
+    Sampler,
+    /// Storage image
+    Image,
+    UniformBuffer,
+    StorageBuffer,
+}
+
+#[cfg(feature = "shaderc")]
+impl From<ResourceKind> for shaderc::ResourceKind {
+    fn from(kind: ResourceKind) -> Self {
+        match kind {
+            ResourceKind::Texture => Self::Texture,
+            ResourceKind::Sampler => Self::Sampler,
+            ResourceKind::Image => Self::Image,
+            ResourceKind::UniformBuffer => Self::Buffer,
+            ResourceKind::StorageBuffer => Self::StorageBuffer,
+        }
+    }
+}
+
+/// GLSL profile, mirroring `shaderc::GlslProfile`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GlslProfile {
+    None,
+    Core,
+    Compatibility,
+    Es,
+}
+
+#[cfg(feature = "shaderc")]
+impl From<GlslProfile> for shaderc::GlslProfile {
+    fn from(profile: GlslProfile) -> Self {
+        match profile {
+            GlslProfile::None => Self::None,
+            GlslProfile::Core => Self::Core,
+            GlslProfile::Compatibility => Self::Compatibility,
+            GlslProfile::Es => Self::Es,
+        }
+    }
+}
+
+/// Environment the SPIR-V is consumed by
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TargetEnv {
+    Vulkan,
+    /// OpenGL with `GL_ARB_gl_spirv`
+    OpenGL,
+}
+
+/// Vulkan version number, as used for [`BuildOptions::target_version`]
+pub const fn vulkan_version(minor: u32) -> u32 {
+    (1 << 22) | (minor << 12)
+}
+
+/// Language of the shader source code
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SourceLanguage {
+    Glsl,
+    Hlsl,
+    #[cfg(feature = "wgsl")]
+    Wgsl,
+}
+
 #[derive(Clone)]
 pub struct BuildOptions {
-    pub kind: Option<shaderc::ShaderKind>,
+    pub source_language: SourceLanguage,
+    pub kind: Option<ShaderKind>,
+    /// Kind used when the kind can neither be inferred from the path nor from the source
+    pub default_kind: Option<ShaderKind>,
+    /// Names of directories implying the kind of the shaders they contain, see [`infer_kind`]
+    pub kind_dirs: Cow<'static, [(Cow<'static, str>, ShaderKind)]>,
     pub version: Option<u32>,
+    /// Profile forced along with [`BuildOptions::version`]
+    pub profile: GlslProfile,
     pub debug: bool,
     /// When parsing build options in the proc macro,
     /// the definitions could have the `Vec<(String, Option<String>)>` type.
@@ -27,33 +243,84 @@ pub struct BuildOptions {
     /// Using [`Cow`] combines both these types.
     #[allow(clippy::type_complexity)]
     pub definitions: Cow<'static, [(Cow<'static, str>, Option<Cow<'static, str>>)]>,
-    pub optimization: shaderc::OptimizationLevel,
+    pub optimization: OptimizationLevel,
+    pub target_env: TargetEnv,
+    /// Version of the target environment: see [`vulkan_version`] for Vulkan,
+    /// and e.g. `450` for OpenGL 4.5
     pub target_version: u32,
+    /// SPIR-V version as `(major, minor)`. Defaults to the version of the target environment.
+    pub spirv_version: Option<(u8, u8)>,
+    /// Name of the entry point function. Defaults to `main` when omitted.
+    pub entry_point: Option<Cow<'static, str>>,
+    /// Absolute path of a file prepended to GLSL sources
+    pub prelude: Option<Cow<'static, str>>,
+    /// Absolute directories searched in order for `#include <...>`
+    pub include_paths: Cow<'static, [Cow<'static, str>]>,
+    /// Shader libraries of dependencies, as the upper-case `links` name of the crate providing the
+    /// library and the absolute directory of the library, used for `#include <name/...>`. The
+    /// directory may not exist, which is only an error when a file of the library is included.
+    #[allow(clippy::type_complexity)]
+    pub shader_libraries: Cow<'static, [(Cow<'static, str>, Cow<'static, str>)]>,
+    /// Sources served for includes of the given names before searching the filesystem
+    #[allow(clippy::type_complexity)]
+    pub virtual_includes: Cow<'static, [(Cow<'static, str>, Cow<'static, str>)]>,
+    pub warnings: Warnings,
+    /// Assign bindings to resources without `layout(binding = ...)`
+    pub auto_bind_uniforms: bool,
+    /// Assign locations to inputs and outputs without `layout(location = ...)`
+    pub auto_map_locations: bool,
+    /// First binding automatically assigned to each kind of resource
+    pub binding_bases: Cow<'static, [(ResourceKind, u32)]>,
+    /// Offset added to the bindings of the resources in each descriptor set,
+    /// as `(set, shift)` pairs
+    pub set_binding_shifts: Cow<'static, [(u32, u32)]>,
 }
 
 impl Default for BuildOptions {
     fn default() -> Self {
         Self {
+            source_language: SourceLanguage::Glsl,
             kind: None,
+            default_kind: None,
+            kind_dirs: Cow::default(),
             version: None,
+            profile: GlslProfile::None,
             debug: !cfg!(feature = "strip"),
             definitions: Cow::default(),
             optimization: if cfg!(feature = "default-optimize-zero") {
-                shaderc::OptimizationLevel::Zero
+                OptimizationLevel::Zero
             } else {
-                shaderc::OptimizationLevel::Performance
+                OptimizationLevel::Performance
             },
+            target_env: TargetEnv::Vulkan,
             target_version: if cfg!(feature = "default-version-vulkan1_4") {
-                shaderc::EnvVersion::Vulkan1_4
+                vulkan_version(4)
             } else if cfg!(feature = "default-version-vulkan1_3") {
-                shaderc::EnvVersion::Vulkan1_3
+                vulkan_version(3)
             } else if cfg!(feature = "default-version-vulkan1_2") {
-                shaderc::EnvVersion::Vulkan1_2
+                vulkan_version(2)
             } else if cfg!(feature = "default-version-vulkan1_1") {
-                shaderc::EnvVersion::Vulkan1_1
+                vulkan_version(1)
+            } else {
+                vulkan_version(0)
+            },
+            spirv_version: None,
+            entry_point: None,
+            prelude: None,
+            include_paths: Cow::default(),
+            shader_libraries: Cow::default(),
+            virtual_includes: Cow::default(),
+            warnings: if cfg!(feature = "default-warnings-allow") {
+                Warnings::Allow
+            } else if cfg!(feature = "default-warnings-warn") {
+                Warnings::Warn
             } else {
-                shaderc::EnvVersion::Vulkan1_0
-            } as u32,
+                Warnings::Deny
+            },
+            auto_bind_uniforms: true,
+            auto_map_locations: false,
+            binding_bases: Cow::default(),
+            set_binding_shifts: Cow::default(),
         }
     }
 }
@@ -67,6 +334,19 @@ pub struct Builder {
 
 impl Builder {
     pub fn build(self) -> Result<Output> {
+        #[cfg(feature = "wgsl")]
+        if self.options.source_language == SourceLanguage::Wgsl {
+            return self.build_naga();
+        }
+
+        #[cfg(feature = "shaderc")]
+        return self.build_shaderc();
+        #[cfg(not(feature = "shaderc"))]
+        return self.build_naga();
+    }
+
+    #[cfg(feature = "shaderc")]
+    fn build_shaderc(self) -> Result<Output> {
         let Self {
             src,
             path: src_path,
@@ -75,24 +355,51 @@ impl Builder {
 
         let path_str = src_path.to_string_lossy().into_owned();
         let sources = RefCell::new(vec![path_str.clone()]);
+        // names of the included files containing `#pragma once`
+        let included_once = RefCell::new(Vec::new());
 
         let mut options = shaderc::CompileOptions::new().unwrap();
+        options.set_source_language(match build_options.source_language {
+            SourceLanguage::Glsl => shaderc::SourceLanguage::GLSL,
+            SourceLanguage::Hlsl => shaderc::SourceLanguage::HLSL,
+            #[cfg(feature = "wgsl")]
+            SourceLanguage::Wgsl => unreachable!(),
+        });
         options.set_include_callback(|name, ty, src, _depth| {
-            let path = match ty {
-                shaderc::IncludeType::Relative => Path::new(src).parent().unwrap().join(name),
-                shaderc::IncludeType::Standard => {
-                    Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(name)
+            // virtual sources are not files, so they are neither tracked nor watched
+            let (resolved_name, content) = if let Some((_, content)) = build_options
+                .virtual_includes
+                .iter()
+                .find(|(virtual_name, _)| virtual_name == name)
+            {
+                (name.to_owned(), content.to_string())
+            } else {
+                let path = match ty {
+                    shaderc::IncludeType::Relative => Path::new(src).parent().unwrap().join(name),
+                    shaderc::IncludeType::Standard => {
+                        resolve_standard_include(name, &build_options)?
+                    }
+                };
+                // the same file may be included through different paths
+                let path = path
+                    .canonicalize()
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+                let path_str = path.to_str().ok_or("non-unicode path")?.to_owned();
+                let mut sources = sources.borrow_mut();
+                if !sources.contains(&path_str) {
+                    sources.push(path_str.clone());
                 }
+                let content = fs::read_to_string(path).map_err(|x| x.to_string())?;
+                (path_str, content)
             };
-            let path_str = path.to_str().ok_or("non-unicode path")?.to_owned();
-            sources.borrow_mut().push(path_str.clone());
+            let content = pragma_once(&resolved_name, content, &mut included_once.borrow_mut());
             Ok(shaderc::ResolvedInclude {
-                resolved_name: path_str,
-                content: fs::read_to_string(path).map_err(|x| x.to_string())?,
+                resolved_name,
+                content,
             })
         });
         if let Some(version) = build_options.version {
-            options.set_forced_version_profile(version, shaderc::GlslProfile::None);
+            options.set_forced_version_profile(version, build_options.profile.into());
         }
         for (name, value) in &*build_options.definitions {
             options.add_macro_definition(name, value.as_ref().map(|x| &x[..]));
@@ -100,46 +407,508 @@ impl Builder {
         if build_options.debug {
             options.set_generate_debug_info();
         }
-        options.set_optimization_level(build_options.optimization);
-        options.set_target_env(shaderc::TargetEnv::Vulkan, build_options.target_version);
-        options.set_auto_bind_uniforms(true);
+        options.set_optimization_level(build_options.optimization.into());
+        let target_env = match build_options.target_env {
+            TargetEnv::Vulkan => shaderc::TargetEnv::Vulkan,
+            TargetEnv::OpenGL => shaderc::TargetEnv::OpenGL,
+        };
+        options.set_target_env(target_env, build_options.target_version);
+        if let Some(version) = build_options.spirv_version {
+            options.set_target_spirv(match version {
+                (1, 0) => shaderc::SpirvVersion::V1_0,
+                (1, 1) => shaderc::SpirvVersion::V1_1,
+                (1, 2) => shaderc::SpirvVersion::V1_2,
+                (1, 3) => shaderc::SpirvVersion::V1_3,
+                (1, 4) => shaderc::SpirvVersion::V1_4,
+                (1, 5) => shaderc::SpirvVersion::V1_5,
+                (1, 6) => shaderc::SpirvVersion::V1_6,
+                (major, minor) => {
+                    return Err(Error(format!("unsupported SPIR-V version {major}.{minor}")))
+                }
+            });
+        }
+        options.set_auto_bind_uniforms(build_options.auto_bind_uniforms);
+        options.set_auto_map_locations(build_options.auto_map_locations);
+        for &(kind, base) in &*build_options.binding_bases {
+            options.set_binding_base(kind.into(), base);
+        }
+        if build_options.warnings == Warnings::Allow {
+            options.set_suppress_warnings();
+        }
 
         let kind = build_options
             .kind
-            .or_else(|| {
-                src_path
-                    .extension()
-                    .and_then(|x| x.to_str().and_then(extension_kind))
-            })
-            .unwrap_or(shaderc::ShaderKind::InferFromSource);
+            .or_else(|| infer_kind(&src_path, &build_options.kind_dirs))
+            .or(build_options.default_kind)
+            .unwrap_or(ShaderKind::InferFromSource);
+        let src = if build_options.source_language == SourceLanguage::Glsl
+            && kind != ShaderKind::SpirvAssembly
+        {
+            prepend_prelude(src, &build_options, &mut sources.borrow_mut())?
+        } else {
+            src
+        };
 
         static COMPILER: OnceLock<shaderc::Compiler> = OnceLock::new();
         let compiler = COMPILER.get_or_init(|| shaderc::Compiler::new().unwrap());
-        let out = compiler.compile_into_spirv(&src, kind, &path_str, "main", Some(&options))?;
-        if out.get_num_warnings() != 0 {
-            return Err(shaderc::Error::InternalError(out.get_warning_messages()));
-        }
+        let entry_point = build_options.entry_point.as_deref().unwrap_or("main");
+        let out = if kind == ShaderKind::SpirvAssembly {
+            compiler.assemble(&src, Some(&options))?
+        } else {
+            compiler.compile_into_spirv(
+                &src,
+                kind.into(),
+                &path_str,
+                entry_point,
+                Some(&options),
+            )?
+        };
+        let warnings = match build_options.warnings {
+            _ if out.get_num_warnings() == 0 => Vec::new(),
+            Warnings::Deny => return Err(Error(out.get_warning_messages())),
+            Warnings::Warn => out
+                .get_warning_messages()
+                .lines()
+                .filter(|x| !x.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            Warnings::Allow => Vec::new(),
+        };
         mem::drop(options);
 
+        let mut spv = out.as_binary().to_vec();
+        shift_bindings(&mut spv, &build_options.set_binding_shifts)?;
+
         #[cfg(feature = "reflection")]
-        let entry_points = spirq::ReflectConfig::new()
-            .spv(out.as_binary())
-            .ref_all_rscs(true)
-            .gen_unique_names(true)
-            .reflect()
-            .unwrap();
+        let entry_points = reflect(&spv).unwrap();
 
         Ok(Output {
             sources: sources.into_inner(),
-            spv: out.as_binary().into(),
+            entry_point: spirv_entry_point(&spv).unwrap_or_default(),
+            spv,
+            warnings,
             #[cfg(feature = "reflection")]
             entry_points,
         })
     }
 }
 
-pub fn extension_kind(ext: &str) -> Option<shaderc::ShaderKind> {
-    use shaderc::ShaderKind::*;
+#[cfg(any(feature = "wgsl", not(feature = "shaderc")))]
+impl Builder {
+    /// Compile with naga, which supports a subset of the options and GLSL features of shaderc
+    fn build_naga(self) -> Result<Output> {
+        use naga::back::spv;
+
+        let Self {
+            src,
+            path: src_path,
+            options: build_options,
+        } = self;
+
+        let path_str = src_path.to_string_lossy().into_owned();
+
+        let kind = build_options
+            .kind
+            .or_else(|| infer_kind(&src_path, &build_options.kind_dirs))
+            .or(build_options.default_kind);
+        if kind == Some(ShaderKind::SpirvAssembly) {
+            return Err(Error(
+                "SPIR-V assembly requires the `shaderc` feature".to_owned(),
+            ));
+        }
+        let stage = kind.and_then(naga_stage);
+
+        let mut sources = vec![path_str.clone()];
+        let src = if build_options.source_language == SourceLanguage::Glsl {
+            prepend_prelude(src, &build_options, &mut sources)?
+        } else {
+            src
+        };
+
+        let mut module = match build_options.source_language {
+            #[cfg(feature = "wgsl")]
+            SourceLanguage::Wgsl => {
+                if !build_options.definitions.is_empty() {
+                    return Err(Error(
+                        "WGSL does not support preprocessor definitions".to_owned(),
+                    ));
+                }
+                naga::front::wgsl::parse_str(&src)
+                    .map_err(|e| Error(e.emit_to_string_with_path(&src, &path_str)))?
+            }
+            #[cfg(not(feature = "shaderc"))]
+            SourceLanguage::Glsl => {
+                let stage = stage.ok_or_else(|| {
+                    Error("the shader kind must be given to compile GLSL with naga".to_owned())
+                })?;
+                if build_options.version.is_some() {
+                    return Err(Error(
+                        "naga does not support forcing the GLSL version".to_owned(),
+                    ));
+                }
+                let mut options = naga::front::glsl::Options::from(stage);
+                for (name, value) in &*build_options.definitions {
+                    options.defines.insert(
+                        name.to_string(),
+                        value.as_deref().unwrap_or_default().to_owned(),
+                    );
+                }
+                naga::front::glsl::Frontend::default()
+                    .parse(&options, &src)
+                    .map_err(|e| Error(e.emit_to_string(&src)))?
+            }
+            _ => {
+                return Err(Error(format!(
+                    "{:?} shaders can only be compiled with shaderc",
+                    build_options.source_language
+                )))
+            }
+        };
+
+        // GLSL entry points are always called `main` in the source
+        if build_options.source_language == SourceLanguage::Glsl {
+            if let Some(name) = &build_options.entry_point {
+                for entry_point in &mut module.entry_points {
+                    entry_point.name = name.to_string();
+                }
+            }
+        }
+
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|e| Error(e.emit_to_string_with_path(&src, &path_str)))?;
+
+        // a WGSL module may contain several entry points, select one by name and stage
+        let entry_points = module
+            .entry_points
+            .iter()
+            .filter(|x| {
+                build_options
+                    .entry_point
+                    .as_ref()
+                    .is_none_or(|name| x.name == *name)
+                    && stage.is_none_or(|stage| x.stage == stage)
+            })
+            .collect::<Vec<_>>();
+        let entry_point = match entry_points[..] {
+            [entry_point] => entry_point,
+            [] => return Err(Error("no matching entry point".to_owned())),
+            _ => {
+                return Err(Error(
+                    "several entry points match, select one with the `entry` or `kind` options"
+                        .to_owned(),
+                ))
+            }
+        };
+
+        if build_options.target_env != TargetEnv::Vulkan {
+            return Err(Error("naga can only target Vulkan".to_owned()));
+        }
+        // naga requires explicit bindings and locations, so automatic assignment does not apply.
+        // Optimization, warnings, include paths and virtual includes are ignored, as documented in
+        // the README.
+        if !build_options.binding_bases.is_empty() {
+            return Err(Error(
+                "binding bases can only be used with shaderc".to_owned(),
+            ));
+        }
+        let mut options = spv::Options {
+            lang_version: build_options
+                .spirv_version
+                .unwrap_or_else(|| spirv_version(build_options.target_version)),
+            ..Default::default()
+        };
+        options
+            .flags
+            .set(spv::WriterFlags::DEBUG, build_options.debug);
+        let pipeline_options = spv::PipelineOptions {
+            shader_stage: entry_point.stage,
+            entry_point: entry_point.name.clone(),
+        };
+        let mut spv = spv::write_vec(&module, &info, &options, Some(&pipeline_options))
+            .map_err(|e| Error(e.to_string()))?;
+        shift_bindings(&mut spv, &build_options.set_binding_shifts)?;
+
+        #[cfg(feature = "reflection")]
+        let entry_points = reflect(&spv).unwrap();
+
+        Ok(Output {
+            sources,
+            entry_point: entry_point.name.clone(),
+            spv,
+            warnings: Vec::new(),
+            #[cfg(feature = "reflection")]
+            entry_points,
+        })
+    }
+}
+
+/// Resolve `#include <name>` in the shader library named by the first component of `name`,
+/// or else in the include paths
+#[cfg(feature = "shaderc")]
+fn resolve_standard_include(
+    name: &str,
+    build_options: &BuildOptions,
+) -> std::result::Result<PathBuf, String> {
+    if let Some((library, path)) = name.split_once('/') {
+        let library = library.to_uppercase().replace('-', "_");
+        if let Some((_, dir)) = build_options
+            .shader_libraries
+            .iter()
+            .find(|(x, _)| *x == library)
+        {
+            let dir = Path::new(&**dir)
+                .canonicalize()
+                .map_err(|e| format!("shader library `{library}` in `{dir}`: {e}"))?;
+            return Ok(dir.join(path));
+        }
+    }
+    build_options
+        .include_paths
+        .iter()
+        .map(|dir| Path::new(&**dir).join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("`{name}` not found in the include paths"))
+}
+
+/// Handle `#pragma once`, which glslang does not support: the content of a file containing it is
+/// empty when the file was already included
+#[cfg(feature = "shaderc")]
+fn pragma_once(name: &str, content: String, included_once: &mut Vec<String>) -> String {
+    let is_pragma_once = |line: &str| {
+        let line = line.trim_start();
+        line.starts_with('#')
+            && line[1..]
+                .split_whitespace()
+                .eq(["pragma", "once"].iter().copied())
+    };
+    if !content.lines().any(is_pragma_once) {
+        return content;
+    }
+    if included_once.iter().any(|x| x == name) {
+        return String::new();
+    }
+    included_once.push(name.to_owned());
+    // keep the line numbers of the file
+    content
+        .lines()
+        .map(|line| if is_pragma_once(line) { "" } else { line })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prepend the prelude of the options to a GLSL source, keeping the line numbers of the source
+fn prepend_prelude(
+    src: String,
+    build_options: &BuildOptions,
+    sources: &mut Vec<String>,
+) -> Result<String> {
+    let path = match &build_options.prelude {
+        Some(path) => path,
+        None => return Ok(src),
+    };
+    let prelude = fs::read_to_string(&**path).map_err(|e| Error(format!("{path}: {e}")))?;
+    if !sources.iter().any(|x| x == path) {
+        sources.push(path.to_string());
+    }
+    // since GLSL 330, `#line` sets the number of the line following it
+    Ok(format!("{prelude}\n#line 1\n{src}"))
+}
+
+/// Stage of a naga entry point with the given kind
+#[cfg(any(feature = "wgsl", not(feature = "shaderc")))]
+fn naga_stage(kind: ShaderKind) -> Option<naga::ShaderStage> {
+    use ShaderKind::*;
+    Some(match kind {
+        Vertex | DefaultVertex => naga::ShaderStage::Vertex,
+        Fragment | DefaultFragment => naga::ShaderStage::Fragment,
+        Compute | DefaultCompute => naga::ShaderStage::Compute,
+        Task | DefaultTask => naga::ShaderStage::Task,
+        Mesh | DefaultMesh => naga::ShaderStage::Mesh,
+        _ => return None,
+    })
+}
+
+/// SPIR-V version used by a Vulkan version
+#[cfg(any(feature = "wgsl", not(feature = "shaderc")))]
+fn spirv_version(target_version: u32) -> (u8, u8) {
+    if target_version >= vulkan_version(3) {
+        (1, 6)
+    } else if target_version >= vulkan_version(2) {
+        (1, 5)
+    } else if target_version >= vulkan_version(1) {
+        (1, 3)
+    } else {
+        (1, 0)
+    }
+}
+
+#[cfg(feature = "reflection")]
+pub fn reflect(spv: &[u32]) -> std::result::Result<Vec<spirq::entry_point::EntryPoint>, String> {
+    spirq::ReflectConfig::new()
+        .spv(spv)
+        .ref_all_rscs(true)
+        .gen_unique_names(true)
+        .reflect()
+        .map_err(|e| e.to_string())
+}
+
+/// Magic number at the start of every SPIR-V module
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+/// Convert a SPIR-V binary into words.
+/// Modules stored with the opposite endianness are byte-swapped.
+#[allow(dead_code)]
+pub fn spirv_words(bytes: &[u8]) -> std::result::Result<Vec<u32>, String> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(format!(
+            "SPIR-V binary size ({} bytes) is not a multiple of the word size",
+            bytes.len()
+        ));
+    }
+    let mut words = bytes
+        .chunks_exact(4)
+        .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+        .collect::<Vec<_>>();
+    match words.first() {
+        Some(&SPIRV_MAGIC) => {}
+        Some(magic) if magic.swap_bytes() == SPIRV_MAGIC => {
+            for word in &mut words {
+                *word = word.swap_bytes();
+            }
+        }
+        _ => return Err("not a SPIR-V binary (missing magic number)".to_owned()),
+    }
+    // the header consists of the magic number, version, generator, bound and schema
+    if words.len() < 5 {
+        return Err("SPIR-V binary is missing its header".to_owned());
+    }
+    Ok(words)
+}
+
+/// Add the shift of each descriptor set to the `Binding` decorations of its resources
+pub fn shift_bindings(words: &mut [u32], shifts: &[(u32, u32)]) -> Result<()> {
+    const OP_DECORATE: u32 = 71;
+    const DECORATION_BINDING: u32 = 33;
+    const DECORATION_DESCRIPTOR_SET: u32 = 34;
+
+    if shifts.is_empty() {
+        return Ok(());
+    }
+
+    // operands of `OpDecorate` are the target id, the decoration and its literals
+    let mut decorations = Vec::new();
+    let mut i = 5;
+    while i < words.len() {
+        let word_count = (words[i] >> 16) as usize;
+        if word_count == 0 || i + word_count > words.len() {
+            break;
+        }
+        if words[i] & 0xffff == OP_DECORATE && word_count == 4 {
+            decorations.push((i, words[i + 1], words[i + 2]));
+        }
+        i += word_count;
+    }
+
+    for &(i, id, decoration) in &decorations {
+        if decoration != DECORATION_BINDING {
+            continue;
+        }
+        let set = decorations
+            .iter()
+            .find(|&&(_, x, decoration)| x == id && decoration == DECORATION_DESCRIPTOR_SET)
+            .map_or(0, |&(j, ..)| words[j + 3]);
+        if let Some(&(_, shift)) = shifts.iter().find(|&&(x, _)| x == set) {
+            words[i + 3] = words[i + 3].checked_add(shift).ok_or_else(|| {
+                Error(format!(
+                    "binding shift of descriptor set {set} overflows binding {}",
+                    words[i + 3]
+                ))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Name of the first entry point declared in a SPIR-V module
+#[allow(dead_code)]
+pub fn spirv_entry_point(words: &[u32]) -> Option<String> {
+    const OP_ENTRY_POINT: u32 = 15;
+
+    // skip the header
+    let mut i = 5;
+    while i < words.len() {
+        let word_count = (words[i] >> 16) as usize;
+        let opcode = words[i] & 0xffff;
+        if word_count == 0 || i + word_count > words.len() {
+            return None;
+        }
+        // operands are the execution model, the function id and the name
+        if opcode == OP_ENTRY_POINT && word_count > 3 {
+            let name = words[i + 3..i + word_count]
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .take_while(|&byte| byte != 0)
+                .collect();
+            return String::from_utf8(name).ok();
+        }
+        i += word_count;
+    }
+    None
+}
+
+/// Kind of a shader from its file name, e.g. `lighting.frag` or `lighting.frag.glsl`,
+/// or else from the nearest directory containing it that is listed in `kind_dirs`
+pub fn infer_kind(
+    path: &Path,
+    kind_dirs: &[(Cow<'static, str>, ShaderKind)],
+) -> Option<ShaderKind> {
+    let file_name = path.file_name()?.to_str()?;
+    file_name
+        .split_once('.')
+        .and_then(|(_, extensions)| extensions.rsplit('.').find_map(extension_kind))
+        .or_else(|| {
+            path.ancestors()
+                .skip(1)
+                .filter_map(|dir| dir.file_name()?.to_str())
+                .find_map(|name| {
+                    kind_dirs
+                        .iter()
+                        .find(|(dir, _)| dir == name)
+                        .map(|&(_, kind)| kind)
+                })
+        })
+}
+
+/// Kind falling back to the given kind when the source has no `#pragma shader_stage`
+#[allow(dead_code)]
+pub fn default_kind(kind: ShaderKind) -> Option<ShaderKind> {
+    use ShaderKind::*;
+    Some(match kind {
+        Vertex => DefaultVertex,
+        Fragment => DefaultFragment,
+        Compute => DefaultCompute,
+        Geometry => DefaultGeometry,
+        TessControl => DefaultTessControl,
+        TessEvaluation => DefaultTessEvaluation,
+        RayGeneration => DefaultRayGeneration,
+        AnyHit => DefaultAnyHit,
+        ClosestHit => DefaultClosestHit,
+        Miss => DefaultMiss,
+        Intersection => DefaultIntersection,
+        Callable => DefaultCallable,
+        Task => DefaultTask,
+        Mesh => DefaultMesh,
+        _ => return None,
+    })
+}
+
+pub fn extension_kind(ext: &str) -> Option<ShaderKind> {
+    use ShaderKind::*;
     Some(match ext {
         "vert" => Vertex,
         "frag" => Fragment,
@@ -1,5 +1,8 @@
 use crate::ShaderData;
-pub use build::BuildOptions;
+pub use build::{
+    BuildOptions, GlslProfile, OptimizationLevel, ResourceKind, ShaderKind, SourceLanguage,
+    TargetEnv, Warnings,
+};
 use notify::{recommended_watcher, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use std::borrow::Cow;
 use std::fs;
@@ -92,6 +95,9 @@ impl HotReloadingData {
         };
         match builder.build() {
             Ok(output) => {
+                for warning in &output.warnings {
+                    eprintln!("{warning}");
+                }
                 self.data = Some(output.spv);
                 // TODO update sources and remove `#[allow(dead_code)]` on `output.sources`
             }
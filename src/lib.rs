@@ -1,7 +1,4 @@
 pub use vk_shader_macros_impl::*;
-// TODO hide from public API
-#[cfg(feature = "hot-reloading")]
-pub use shaderc::{OptimizationLevel, ShaderKind};
 
 #[cfg(feature = "hot-reloading")]
 mod hot_reloading;
@@ -15,6 +12,8 @@ pub use reflection::*;
 
 pub struct ShaderData {
     pub compile_time_spv: &'static [u32],
+    /// Name of the shader's entry point, e.g. for `VkPipelineShaderStageCreateInfo::pName`
+    pub entry_point: &'static std::ffi::CStr,
     #[cfg(feature = "hot-reloading")]
     #[doc(hidden)]
     pub hot_reloading: Option<std::sync::Mutex<HotReloadingData>>,
@@ -0,0 +1,41 @@
+use vk_shader_macros::{glsl, ShaderData};
+
+static SHIFTED: ShaderData = glsl! {
+    kind: frag, optimize: zero, set_binding_shift: 1 16, binding_base: texture 4,
+    auto_map_locations: true,
+    r#"
+#version 450
+
+layout(set = 0, binding = 2) uniform sampler2D explicit_tex;
+layout(set = 1, binding = 2) uniform sampler2D shifted_tex;
+layout(set = 0) uniform sampler2D auto_tex;
+
+in vec2 uv;
+out vec4 color;
+
+void main() {
+    color = texture(explicit_tex, uv) + texture(shifted_tex, uv) + texture(auto_tex, uv);
+}
+"#
+};
+
+/// Values of the `Binding` decorations, in the order of the module
+fn bindings(words: &[u32]) -> Vec<u32> {
+    let mut out = Vec::new();
+    let mut i = 5;
+    while i < words.len() {
+        let word_count = (words[i] >> 16) as usize;
+        // `OpDecorate %id Binding <binding>`
+        if words[i] & 0xffff == 71 && word_count == 4 && words[i + 2] == 33 {
+            out.push(words[i + 3]);
+        }
+        i += word_count;
+    }
+    out.sort();
+    out
+}
+
+#[test]
+fn binding_options() {
+    assert_eq!(bindings(SHIFTED.compile_time_spv), [2, 4, 18]);
+}
@@ -0,0 +1,35 @@
+use vk_shader_macros::{glsl, ShaderData};
+
+// `CONFIG_DEFINE` is defined by the `vk-shader-macros.toml` in the crate root, which applies to the
+// shaders of every test of this crate and is excluded from the published package
+#[allow(dead_code)]
+static CONFIG: ShaderData = glsl! {
+    kind: vert,
+    r#"
+#version 450
+
+#if CONFIG_DEFINE != 1
+#error "configuration file not applied"
+#endif
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
+
+#[allow(dead_code)]
+static OVERRIDDEN: ShaderData = glsl! {
+    kind: vert, define: CONFIG_DEFINE 2,
+    r#"
+#version 450
+
+#if CONFIG_DEFINE != 2
+#error "configuration file not overridden"
+#endif
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
@@ -0,0 +1,11 @@
+use vk_shader_macros::include_glsl_dir;
+
+include_glsl_dir!(mod shaders = "shaders", version: 450, optimize: size);
+
+#[test]
+fn items() {
+    assert_eq!(shaders::ALL.len(), 2);
+    assert_eq!(shaders::ALL[0].0, "blur.comp");
+    assert!(std::ptr::eq(shaders::ALL[0].1, &shaders::BLUR_COMP));
+    assert!(std::ptr::eq(shaders::ALL[1].1, &shaders::EXAMPLE_VERT));
+}
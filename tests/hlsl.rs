@@ -0,0 +1,24 @@
+use vk_shader_macros::{hlsl, include_hlsl, ShaderData};
+
+static PIXEL: ShaderData = include_hlsl!("test.hlsl", kind: frag, entry: "PSMain");
+
+#[allow(dead_code)]
+static COMPUTE: ShaderData = hlsl! {
+    kind: comp, entry: "CSMain",
+    r#"
+// Relative include (resolved from the macro invocation)
+#include "test.hlsli"
+
+RWStructuredBuffer<uint> output : register(u0);
+
+[numthreads(64, 1, 1)]
+void CSMain(uint3 id : SV_DispatchThreadID) {
+    output[id.x] = square(id.x);
+}
+"#
+};
+
+#[test]
+fn entry_point() {
+    assert_eq!(PIXEL.entry_point.to_bytes(), b"PSMain");
+}
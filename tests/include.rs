@@ -3,3 +3,10 @@ use vk_shader_macros::{include_glsl, ShaderData};
 #[allow(dead_code)]
 static TEST: ShaderData =
     include_glsl!("../example.vert", version: 450, optimize: size, target: vulkan1_1);
+
+#[allow(dead_code)]
+static MANIFEST_RELATIVE: ShaderData = include_glsl!("$manifest/example.vert");
+
+// the crate is not part of a workspace, so this resolves relative to the crate root
+#[allow(dead_code)]
+static WORKSPACE_RELATIVE: ShaderData = include_glsl!("$workspace/example.vert");
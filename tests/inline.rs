@@ -41,3 +41,182 @@ void main() {
 }
 "#
 };
+
+#[allow(dead_code)]
+static INCLUDE_PATH: ShaderData = glsl! {
+    include_path: "tests", kind: vert,
+    r#"
+#version 450
+
+// Standard include (resolved from the include path)
+#include <test.glsl>
+
+void main() {
+    gl_Position = test(gl_Position);
+}
+"#
+};
+
+#[allow(dead_code)]
+static VIRTUAL_INCLUDE: ShaderData = glsl! {
+    virtual_include: "consts.glsl" => "const float SCALE = 2.0;",
+    kind: vert,
+    r#"
+#version 450
+
+// Virtual include (supplied by the macro invocation)
+#include <consts.glsl>
+
+void main() {
+    gl_Position = vec4(SCALE);
+}
+"#
+};
+
+#[allow(dead_code)]
+static TYPED_DEFINES: ShaderData = glsl! {
+    kind: vert, define: COUNT 4u32, define: SCALE -0.5, define: WEIGHT 2f32, define: ENABLED true,
+    r#"
+#version 450
+
+void main() {
+    uint count = COUNT;
+    gl_Position = ENABLED ? vec4(SCALE * WEIGHT * float(count)) : vec4(0);
+}
+"#
+};
+
+static DEFINE_IF: ShaderData = glsl! {
+    kind: vert, define_if: (debug_assertions) DEBUG_VIS, define_if: (not(test)) UNUSED,
+    r#"
+#version 450
+
+void main() {
+#ifdef DEBUG_VIS
+    gl_Position = vec4(1);
+#else
+    gl_Position = vec4(0);
+#endif
+}
+"#
+};
+
+static WITHOUT_DEBUG_VIS: ShaderData = glsl! {
+    kind: vert,
+    r#"
+#version 450
+
+void main() {
+#ifdef DEBUG_VIS
+    gl_Position = vec4(1);
+#else
+    gl_Position = vec4(0);
+#endif
+}
+"#
+};
+
+#[test]
+fn define_if() {
+    assert_eq!(
+        DEFINE_IF.compile_time_spv == WITHOUT_DEBUG_VIS.compile_time_spv,
+        !cfg!(debug_assertions)
+    );
+}
+
+#[allow(dead_code)]
+static ALLOW_WARNINGS: ShaderData = glsl! {
+    kind: vert, warnings: allow,
+    r#"
+#version 450
+#extension GL_VK_SHADER_MACROS_unknown : warn
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
+
+// shader warnings are reported through the deprecation lint
+#[allow(deprecated)]
+static WARN_WARNINGS: ShaderData = glsl! {
+    kind: vert, warnings: warn,
+    r#"
+#version 450
+#extension GL_VK_SHADER_MACROS_unknown : warn
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
+
+#[test]
+fn warnings_still_compile() {
+    assert!(!WARN_WARNINGS.compile_time_spv.is_empty());
+}
+
+#[allow(dead_code)]
+static OPENGL: ShaderData = glsl! {
+    kind: vert, target: opengl4_5,
+    r#"
+#version 450
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
+
+static SPIRV_1_4: ShaderData = glsl! {
+    kind: vert, target: vulkan1_1, spirv: 1_4,
+    r#"
+#version 450
+
+void main() {
+    gl_Position = vec4(0);
+}
+"#
+};
+
+#[test]
+fn spirv_version() {
+    // the version word follows the magic number
+    assert_eq!(SPIRV_1_4.compile_time_spv[1], 0x0001_0400);
+}
+
+#[allow(dead_code)]
+static ES_PROFILE: ShaderData = glsl! {
+    kind: vert, version: 310 es,
+    r#"
+void main() {
+    gl_Position = vec4(0.0);
+}
+"#
+};
+
+#[allow(dead_code)]
+static CORE_PROFILE: ShaderData = glsl! {
+    kind: vert, version: 450, profile: core,
+    r#"
+void main() {
+    gl_Position = vec4(0.0);
+}
+"#
+};
+
+#[allow(dead_code)]
+static PRAGMA_ONCE: ShaderData = glsl! {
+    kind: vert,
+    r#"
+#version 450
+
+// The second include is skipped by `#pragma once`
+#include "once.glsl"
+#include <tests/once.glsl>
+
+void main() {
+    gl_Position = vec4(ONCE);
+}
+"#
+};
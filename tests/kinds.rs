@@ -0,0 +1,11 @@
+use vk_shader_macros::{include_glsl, ShaderData};
+
+#[allow(dead_code)]
+static DOUBLE_EXTENSION: ShaderData = include_glsl!("kinds/lighting.frag.glsl");
+
+#[allow(dead_code)]
+static KIND_DIR: ShaderData =
+    include_glsl!("kinds/compute/clear.glsl", kind_dir: "compute" => comp);
+
+#[allow(dead_code)]
+static DEFAULT_KIND: ShaderData = include_glsl!("kinds/fullscreen.glsl", default_kind: vert);
@@ -0,0 +1,18 @@
+use vk_shader_macros::{glsl, ShaderData};
+
+// the `testlib` shader library is set up in `.cargo/config.toml`
+#[allow(dead_code)]
+static LIBRARY: ShaderData = glsl! {
+    kind: frag,
+    r#"
+#version 450
+
+#include <testlib/color.glsl>
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = library_color();
+}
+"#
+};
@@ -0,0 +1,3 @@
+vec4 library_color() {
+    return vec4(1.0, 0.0, 1.0, 1.0);
+}
@@ -0,0 +1,10 @@
+#![cfg(all(feature = "naga-glsl", not(feature = "shaderc")))]
+
+use vk_shader_macros::{include_glsl, ShaderData};
+
+static COMPUTE: ShaderData = include_glsl!("shaders/blur.comp", define: UNUSED);
+
+#[test]
+fn entry_point() {
+    assert_eq!(COMPUTE.entry_point.to_bytes(), b"main");
+}
@@ -0,0 +1,3 @@
+#pragma once
+
+const float ONCE = 1.0;
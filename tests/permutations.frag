@@ -0,0 +1,13 @@
+#version 450
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0);
+#ifdef RED
+    color.r = 1;
+#endif
+#ifdef GREEN
+    color.g = 1;
+#endif
+}
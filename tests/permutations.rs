@@ -0,0 +1,18 @@
+use vk_shader_macros::include_glsl_permutations;
+
+include_glsl_permutations!(struct Color = "permutations.frag", permute: [RED, GREEN]);
+
+#[test]
+fn permutations() {
+    assert_eq!((Color::RED | Color::GREEN).bits(), 0b11);
+    assert!((Color::RED | Color::GREEN).contains(Color::GREEN));
+    assert!(!Color::default().contains(Color::RED));
+    assert_ne!(
+        Color::default().shader().compile_time_spv,
+        Color::RED.shader().compile_time_spv
+    );
+    assert_ne!(
+        Color::RED.shader().compile_time_spv,
+        Color::GREEN.shader().compile_time_spv
+    );
+}
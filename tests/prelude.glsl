@@ -0,0 +1,4 @@
+#version 460
+#extension GL_EXT_samplerless_texture_functions : require
+
+const vec4 PRELUDE_COLOR = vec4(1);
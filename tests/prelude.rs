@@ -0,0 +1,13 @@
+use vk_shader_macros::{glsl, ShaderData};
+
+#[allow(dead_code)]
+static PRELUDE: ShaderData = glsl! {
+    kind: frag, prelude: "tests/prelude.glsl",
+    r#"
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = PRELUDE_COLOR;
+}
+"#
+};
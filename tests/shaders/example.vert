@@ -0,0 +1,8 @@
+#version 450
+
+// Relative include (resolved from current file)
+#include "../test.glsl"
+
+void main() {
+    gl_Position = test(gl_Position);
+}
@@ -0,0 +1,14 @@
+use vk_shader_macros::{include_spirv, ShaderData};
+
+static COMPUTE: ShaderData = include_spirv!("test.spv");
+
+#[test]
+fn header() {
+    assert_eq!(COMPUTE.compile_time_spv[0], 0x0723_0203);
+    assert_eq!(COMPUTE.compile_time_spv.len(), 35);
+}
+
+#[test]
+fn entry_point() {
+    assert_eq!(COMPUTE.entry_point.to_bytes(), b"main");
+}
@@ -0,0 +1,21 @@
+use vk_shader_macros::{spvasm, ShaderData};
+
+static COMPUTE: ShaderData = spvasm! {
+    r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpEntryPoint GLCompute %main "main"
+               OpExecutionMode %main LocalSize 1 1 1
+       %void = OpTypeVoid
+         %fn = OpTypeFunction %void
+       %main = OpFunction %void None %fn
+      %entry = OpLabel
+               OpReturn
+               OpFunctionEnd
+"#
+};
+
+#[test]
+fn assembled() {
+    assert_eq!(COMPUTE.compile_time_spv[0], 0x0723_0203);
+}
@@ -0,0 +1,19 @@
+#version 450
+
+#ifdef VERTEX
+layout(location = 0) out vec4 color;
+
+void main() {
+    gl_Position = vec4(0);
+    color = vec4(1);
+}
+#endif
+
+#ifdef FRAGMENT
+layout(location = 0) in vec4 color;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = color;
+}
+#endif
@@ -0,0 +1,10 @@
+use vk_shader_macros::{include_glsl_stages, ShaderData};
+
+static MATERIAL: (ShaderData, ShaderData) =
+    include_glsl_stages!("stages.glsl", stages: [vert, frag], optimize: size);
+
+#[test]
+fn stages() {
+    let (vertex, fragment) = &MATERIAL;
+    assert_ne!(vertex.compile_time_spv, fragment.compile_time_spv);
+}
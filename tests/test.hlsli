@@ -0,0 +1,3 @@
+uint square(uint x) {
+    return x * x;
+}
@@ -0,0 +1,12 @@
+#![cfg(feature = "wgsl")]
+
+use vk_shader_macros::{include_wgsl, ShaderData};
+
+static VERTEX: ShaderData = include_wgsl!("test.wgsl", kind: vert);
+static FRAGMENT: ShaderData = include_wgsl!("test.wgsl", entry: "fs_main");
+
+#[test]
+fn entry_points() {
+    assert_eq!(VERTEX.entry_point.to_bytes(), b"vs_main");
+    assert_eq!(FRAGMENT.entry_point.to_bytes(), b"fs_main");
+}